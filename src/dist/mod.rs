@@ -8,16 +8,29 @@ distribution `META.json` files. It supports both the [v1] and [v2] specs.
   [v2]: https://github.com/pgxn/rfcs/pull/3
 
 */
-use std::{borrow::Borrow, collections::HashMap, fs::File, path::Path};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs::File,
+    path::Path,
+    str::FromStr,
+};
 
 use crate::{error::Error, util};
+use email_address::EmailAddress;
 use relative_path::{RelativePath, RelativePathBuf};
-use semver::Version;
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use wax::{Glob, Pattern};
 
 pub(crate) mod v1;
-mod v2;
+pub(crate) mod v2;
+
+pub use v1::{
+    conversion_map, is_core_extension, to_v2_marked, to_v2_reported, ConversionMapping,
+    ConversionReport, ConversionWarning, FALLBACK_MAINTAINER_URL, FROM_V1_PROPERTY,
+};
 
 /// Represents the `meta-spec` object in [`Distribution`].
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -27,7 +40,7 @@ pub struct Spec {
     url: Option<String>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Spec {
@@ -53,7 +66,7 @@ pub struct Maintainer {
     url: Option<String>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Maintainer {
@@ -72,13 +85,106 @@ impl Maintainer {
         self.url.as_deref()
     }
 
+    /// Returns true if the Maintainer URL is the generic
+    /// [`FALLBACK_MAINTAINER_URL`] assigned when converting a v1
+    /// maintainer with no email address or homepage, indicating that the
+    /// Maintainer lacks real contact information.
+    pub fn has_fallback_url(&self) -> bool {
+        self.url() == Some(v1::FALLBACK_MAINTAINER_URL)
+    }
+
+    /// Returns the PGXN user handle from the Maintainer URL if it is a PGXN
+    /// profile link of the form `https://pgxn.org/user/<handle>`, with or
+    /// without a trailing slash. Returns `None` for any other URL or if the
+    /// Maintainer has no URL.
+    pub fn pgxn_handle(&self) -> Option<&str> {
+        let path = self.url()?.strip_prefix("https://pgxn.org/user/")?;
+        let handle = path.strip_suffix('/').unwrap_or(path);
+        if handle.is_empty() || handle.contains(['/', '?', '#']) {
+            return None;
+        }
+        Some(handle)
+    }
+
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
 
+/// Parses a maintainer string, such as `"David <theory@pgxn.org>"`, into a
+/// [`Maintainer`]. If `s` contains an email address, the address and its
+/// display name are used for the maintainer `email` and `name`,
+/// respectively; if the display name is empty, as in `"theory@pgxn.org"`,
+/// `s` itself is used for the name. Otherwise `s` is used for the name and
+/// the email is `None`. This is the same logic used to convert v1
+/// maintainers to v2.
+///
+/// ``` rust
+/// use pgxn_meta::dist::parse_maintainer_string;
+///
+/// let maintainer = parse_maintainer_string("David <theory@pgxn.org>");
+/// assert_eq!("David", maintainer.name());
+/// assert_eq!(Some("theory@pgxn.org"), maintainer.email());
+/// ```
+pub fn parse_maintainer_string(s: &str) -> Maintainer {
+    let (name, email) = match EmailAddress::from_str(s) {
+        Ok(email) => match email.display_part() {
+            "" => (s.to_string(), Some(email.email())),
+            d => (d.to_string(), Some(email.email())),
+        },
+        Err(_) => (s.to_string(), None),
+    };
+
+    Maintainer {
+        name,
+        email,
+        url: None,
+        custom_props: BTreeMap::new(),
+    }
+}
+
+/// Returns true if `s` is a valid maintainer email address, such as
+/// `theory@pgxn.org`. Uses the same email address parser as
+/// [`parse_maintainer_string`] and v1 conversion, but rejects strings with
+/// a display name or angle brackets, such as `David <theory@pgxn.org>`,
+/// since the maintainer `email` property must be a bare address.
+///
+/// ``` rust
+/// use pgxn_meta::dist::is_valid_email;
+///
+/// assert!(is_valid_email("theory@pgxn.org"));
+/// assert!(!is_valid_email("David <theory@pgxn.org>"));
+/// ```
+pub fn is_valid_email(s: &str) -> bool {
+    EmailAddress::from_str(s)
+        .is_ok_and(|email| email.display_part().is_empty() && email.email() == s)
+}
+
+/// Parses a `name@version` identifier, as returned by [`Distribution::id`],
+/// into the distribution name and version. Splits `s` on its last `@`, so
+/// that the version is always the part after it. Returns an error if `s`
+/// has no `@`, if the name is empty, or if the version is not a valid
+/// SemVer version.
+///
+/// ``` rust
+/// use pgxn_meta::dist::parse_id;
+///
+/// let (name, version) = parse_id("pair@0.1.8").unwrap();
+/// assert_eq!("pair", name);
+/// assert_eq!("0.1.8", version.to_string());
+/// ```
+pub fn parse_id(s: &str) -> Result<(String, Version), Error> {
+    let invalid = || Error::Invalid("distribution id", 2, Value::from(s));
+    let (name, version) = s.rsplit_once('@').ok_or_else(invalid)?;
+    if name.is_empty() {
+        return Err(invalid());
+    }
+    let version = Version::parse(version).map_err(|_| invalid())?;
+    Ok((name.to_string(), version))
+}
+
 /// Describes an extension in under `extensions` in [`Contents`].
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct Extension {
@@ -93,7 +199,7 @@ pub struct Extension {
     doc: Option<RelativePathBuf>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Extension {
@@ -125,13 +231,25 @@ impl Extension {
 
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
+
+    /// Returns true if the Extension equals `other` once the paths of both
+    /// are normalized.
+    fn eq_normalized(&self, other: &Extension) -> bool {
+        path_eq(&self.control, &other.control)
+            && path_eq(&self.sql, &other.sql)
+            && opt_path_eq(self.doc(), other.doc())
+            && self.abs_tract == other.abs_tract
+            && self.tle == other.tle
+            && self.custom_props == other.custom_props
+    }
 }
 
 /// Defines a type of module in [`Module`].
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[non_exhaustive]
 pub enum ModuleType {
     /// Indicates an extension shared library module.
     #[serde(rename = "extension")]
@@ -142,6 +260,11 @@ pub enum ModuleType {
     /// Indicates a background worker shared library module.
     #[serde(rename = "bgw")]
     Bgw,
+    /// Holds an unknown module type, such as one defined by a later version
+    /// of the spec. Deserialization without validation accepts any string
+    /// here; validation rejects unknown types.
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl std::fmt::Display for ModuleType {
@@ -151,12 +274,14 @@ impl std::fmt::Display for ModuleType {
             ModuleType::Extension => write!(f, "extension"),
             ModuleType::Hook => write!(f, "hook"),
             ModuleType::Bgw => write!(f, "bgw"),
+            ModuleType::Other(s) => write!(f, "{s}"),
         }
     }
 }
 
 /// Defines the values for the `preload` value in [`Module`]s.
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[non_exhaustive]
 pub enum Preload {
     /// Indicates a module that should be included in
     /// `shared_preload_libraries` and requires a service restart.
@@ -166,6 +291,11 @@ pub enum Preload {
     /// `session_preload_libraries` or `local_preload_libraries`.
     #[serde(rename = "session")]
     Session,
+    /// Holds an unknown preload value, such as one defined by a later
+    /// version of the spec. Deserialization without validation accepts any
+    /// string here; validation rejects unknown values.
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl std::fmt::Display for Preload {
@@ -174,6 +304,7 @@ impl std::fmt::Display for Preload {
         match self {
             Preload::Server => write!(f, "server"),
             Preload::Session => write!(f, "session"),
+            Preload::Other(s) => write!(f, "{s}"),
         }
     }
 }
@@ -193,7 +324,7 @@ pub struct Module {
     doc: Option<RelativePathBuf>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Module {
@@ -224,9 +355,20 @@ impl Module {
 
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
+
+    /// Returns true if the Module equals `other` once the paths of both are
+    /// normalized.
+    fn eq_normalized(&self, other: &Module) -> bool {
+        path_eq(&self.lib, &other.lib)
+            && opt_path_eq(self.doc(), other.doc())
+            && self.kind == other.kind
+            && self.abs_tract == other.abs_tract
+            && self.preload == other.preload
+            && self.custom_props == other.custom_props
+    }
 }
 
 /// Represents an app under `apps` in [`Contents`].
@@ -248,7 +390,7 @@ pub struct App {
     html: Option<RelativePathBuf>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl App {
@@ -289,9 +431,22 @@ impl App {
 
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
+
+    /// Returns true if the App equals `other` once the paths of both are
+    /// normalized.
+    fn eq_normalized(&self, other: &App) -> bool {
+        path_eq(&self.bin, &other.bin)
+            && opt_path_eq(self.lib(), other.lib())
+            && opt_path_eq(self.doc(), other.doc())
+            && opt_path_eq(self.man(), other.man())
+            && opt_path_eq(self.html(), other.html())
+            && self.lang == other.lang
+            && self.abs_tract == other.abs_tract
+            && self.custom_props == other.custom_props
+    }
 }
 
 /// Represents the contents of a distribution, under `contents` in
@@ -299,39 +454,165 @@ impl App {
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct Contents {
     #[serde(skip_serializing_if = "Option::is_none")]
-    extensions: Option<HashMap<String, Extension>>,
+    extensions: Option<BTreeMap<String, Extension>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    modules: Option<HashMap<String, Module>>,
+    modules: Option<BTreeMap<String, Module>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    apps: Option<HashMap<String, App>>,
+    apps: Option<BTreeMap<String, App>>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Contents {
     /// Borrows the Contents extensions object.
-    pub fn extensions(&self) -> Option<&HashMap<String, Extension>> {
+    pub fn extensions(&self) -> Option<&BTreeMap<String, Extension>> {
         self.extensions.as_ref()
     }
 
     /// Borrows the Contents modules object.
-    pub fn modules(&self) -> Option<&HashMap<String, Module>> {
+    pub fn modules(&self) -> Option<&BTreeMap<String, Module>> {
         self.modules.as_ref()
     }
 
     /// Borrows the Contents apps object.
-    pub fn apps(&self) -> Option<&HashMap<String, App>> {
+    pub fn apps(&self) -> Option<&BTreeMap<String, App>> {
         self.apps.as_ref()
     }
 
+    /// Borrows the extension, module, or app named `name`. Looks for
+    /// extensions first, then modules, then apps, and returns the first
+    /// match, so an extension shadows a module or app with the same name.
+    /// Returns `None` if no item has the name.
+    pub fn get(&self, name: &str) -> Option<ContentItem<'_>> {
+        if let Some(ext) = self.extensions().and_then(|e| e.get(name)) {
+            return Some(ContentItem::Extension(ext));
+        }
+        if let Some(module) = self.modules().and_then(|m| m.get(name)) {
+            return Some(ContentItem::Module(module));
+        }
+        self.apps().and_then(|a| a.get(name)).map(ContentItem::App)
+    }
+
+    /// Returns true if the Contents equals `other` once all of their paths
+    /// are normalized, so that paths that differ only in spelling, such as
+    /// `./sql/x.sql` and `sql/x.sql`, compare as equal. All other fields
+    /// must be equal.
+    pub fn eq_normalized(&self, other: &Contents) -> bool {
+        items_eq(
+            self.extensions(),
+            other.extensions(),
+            Extension::eq_normalized,
+        ) && items_eq(self.modules(), other.modules(), Module::eq_normalized)
+            && items_eq(self.apps(), other.apps(), App::eq_normalized)
+            && self.custom_props == other.custom_props
+    }
+
+    /// Returns the name and `sql` path of each extension whose `sql` file
+    /// does not have a `.sql` extension, compared case-insensitively. Such
+    /// paths are likely mistakes, but are not invalid.
+    pub fn nonsql_extension_sql(&self) -> Vec<(String, RelativePathBuf)> {
+        let mut paths: Vec<(String, RelativePathBuf)> = self
+            .extensions()
+            .into_iter()
+            .flatten()
+            .filter(|(_, ext)| {
+                !ext.sql()
+                    .extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case("sql"))
+            })
+            .map(|(name, ext)| (name.clone(), ext.sql().clone()))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Returns the names of all extensions whose `sql` and `control` paths
+    /// refer to the same file once normalized, sorted. An extension needs
+    /// distinct SQL and control files, so such paths are almost certainly a
+    /// mistake.
+    pub fn extension_path_collisions(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .extensions()
+            .into_iter()
+            .flatten()
+            .filter(|(_, ext)| path_eq(ext.sql(), ext.control()))
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Returns the name of each extension with a suspicious layout and the
+    /// reason it is suspicious. A layout is suspicious when the `control`
+    /// file is in the root directory and the `sql` file is nested two or more
+    /// directories deep, or vice versa. This often indicates a path mistake,
+    /// such as the control file guessed as `<name>.control` in the root
+    /// directory when converting v1 metadata.
+    pub fn suspicious_extension_layout(&self) -> Vec<(String, String)> {
+        let mut found: Vec<(String, String)> = self
+            .extensions()
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, ext)| {
+                let (control, sql) = (dir_depth(ext.control()), dir_depth(ext.sql()));
+                let reason = if control == 0 && sql >= 2 {
+                    format!("control file in root directory but sql file {sql} directories deep")
+                } else if sql == 0 && control >= 2 {
+                    format!(
+                        "sql file in root directory but control file {control} directories deep"
+                    )
+                } else {
+                    return None;
+                };
+                Some((name.clone(), reason))
+            })
+            .collect();
+        found.sort();
+        found
+    }
+
+    /// Returns the name and path of each extension `sql` and `control` file,
+    /// module `lib`, and app `bin`.
+    fn essential_paths(&self) -> Vec<(&str, &RelativePath)> {
+        let mut paths = Vec::new();
+        if let Some(exts) = self.extensions() {
+            for (name, ext) in exts {
+                paths.push((name.as_str(), ext.sql().as_relative_path()));
+                paths.push((name.as_str(), ext.control().as_relative_path()));
+            }
+        }
+        if let Some(mods) = self.modules() {
+            for (name, module) in mods {
+                paths.push((name.as_str(), module.lib().as_relative_path()));
+            }
+        }
+        if let Some(apps) = self.apps() {
+            for (name, app) in apps {
+                paths.push((name.as_str(), app.bin().as_relative_path()));
+            }
+        }
+        paths
+    }
+
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
 
+/// Borrows a single item in [`Contents`], as returned by [`Contents::get`].
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ContentItem<'a> {
+    /// An extension.
+    Extension(&'a Extension),
+    /// A module.
+    Module(&'a Module),
+    /// An app.
+    App(&'a App),
+}
+
 /// Represents the classifications of a distribution, under `classifications`
 /// in [`Distribution`].
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -342,7 +623,7 @@ pub struct Classifications {
     categories: Option<Vec<String>>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Classifications {
@@ -356,13 +637,383 @@ impl Classifications {
         self.categories.as_deref()
     }
 
+    /// Returns the tags that have leading or trailing whitespace or that
+    /// contain consecutive spaces. Such tags are allowed by the spec but are
+    /// almost always mistakes that interfere with search.
+    pub fn messy_tags(&self) -> Vec<&str> {
+        messy_terms(self.tags())
+    }
+
+    /// Returns the categories that have leading or trailing whitespace or
+    /// that contain consecutive spaces.
+    pub fn messy_categories(&self) -> Vec<&str> {
+        messy_terms(self.categories())
+    }
+
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
 
+/// Returns true if `a` and `b` are equal once normalized.
+fn path_eq(a: &RelativePath, b: &RelativePath) -> bool {
+    a.normalize() == b.normalize()
+}
+
+/// Returns true if `a` and `b` are both `None` or are equal once normalized.
+fn opt_path_eq(a: Option<&RelativePath>, b: Option<&RelativePath>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => path_eq(a, b),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+/// Returns true if `a` and `b` are both `None` or have the same keys and
+/// `eq` returns true for the values of every key.
+fn items_eq<T>(
+    a: Option<&BTreeMap<String, T>>,
+    b: Option<&BTreeMap<String, T>>,
+    eq: fn(&T, &T) -> bool,
+) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|o| eq(v, o)))
+        }
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+/// Returns the number of directories containing `path`.
+fn dir_depth(path: &RelativePath) -> usize {
+    path.normalize().components().count().saturating_sub(1)
+}
+
+/// Returns the terms that have leading or trailing whitespace or that contain
+/// consecutive spaces.
+fn messy_terms(terms: Option<&[String]>) -> Vec<&str> {
+    terms
+        .unwrap_or_default()
+        .iter()
+        .map(String::as_str)
+        .filter(|t| t.trim() != *t || t.contains("  "))
+        .collect()
+}
+
+/// Parses `license` as an SPDX expression and returns it in canonical form.
+/// See [`Distribution::canonical_license`] for details.
+fn canonical_license(license: &str) -> Result<String, Error> {
+    use spdx::expression::{ExprNode, Operator};
+    use spdx::{LicenseItem, LicenseReq};
+
+    // Precedence of each stack entry, used to determine where parentheses
+    // are required.
+    const OR: u8 = 0;
+    const AND: u8 = 1;
+    const REQ: u8 = 2;
+
+    let expr = spdx::Expression::parse_mode(license, spdx::ParseMode::LAX)?;
+    let mut stack: Vec<(String, u8)> = Vec::new();
+    for node in expr.iter() {
+        match node {
+            ExprNode::Req(er) => {
+                let mut req = er.req.clone();
+                // Display a deprecated GNU ID without `+` as its `-only` ID.
+                if let LicenseItem::Spdx {
+                    id,
+                    or_later: false,
+                } = req.license
+                {
+                    if id.is_gnu() && id.is_deprecated() {
+                        if let Some(only) = spdx::license_id(&format!("{}-only", id.name)) {
+                            req = LicenseReq {
+                                license: LicenseItem::Spdx {
+                                    id: only,
+                                    or_later: false,
+                                },
+                                exception: req.exception,
+                            };
+                        }
+                    }
+                }
+                stack.push((req.to_string(), REQ));
+            }
+            ExprNode::Op(op) => {
+                let (Some((right, rp)), Some((left, lp))) = (stack.pop(), stack.pop()) else {
+                    return Err(Error::Param("invalid license expression"));
+                };
+                let (word, prec) = match op {
+                    Operator::And => ("AND", AND),
+                    Operator::Or => ("OR", OR),
+                };
+                let wrap = |s: String, p: u8| if p < prec { format!("({s})") } else { s };
+                stack.push((
+                    format!("{} {word} {}", wrap(left, lp), wrap(right, rp)),
+                    prec,
+                ));
+            }
+        }
+    }
+
+    match (stack.pop(), stack.is_empty()) {
+        (Some((canon, _)), true) => Ok(canon),
+        _ => Err(Error::Param("invalid license expression")),
+    }
+}
+
+/// Splits `producer` into a tool name and optional version. See
+/// [`Distribution::producer_tool`] for the heuristic.
+fn producer_tool(producer: &str) -> Option<(&str, Option<&str>)> {
+    let is_name = |s: &str| {
+        s.starts_with(|c: char| c.is_ascii_alphabetic())
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+    };
+    let is_version = |s: &str| {
+        let s = s.strip_prefix('v').unwrap_or(s);
+        s.starts_with(|c: char| c.is_ascii_digit())
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+    };
+
+    let producer = producer.trim();
+    let mut words = producer.split_whitespace();
+    let (name, version) = match (words.next()?, words.next(), words.next()) {
+        (name, Some(version), None) => (name, Some(version)),
+        (word, None, None) => match word.split_once('/') {
+            Some((name, version)) => (name, Some(version)),
+            // A bare word must be lowercase to distinguish it from a name.
+            None if word.chars().any(|c| c.is_ascii_uppercase()) => return None,
+            None => (word, None),
+        },
+        _ => return None,
+    };
+
+    match version {
+        Some(v) if !is_version(v) => None,
+        _ if !is_name(name) => None,
+        _ => Some((name, version)),
+    }
+}
+
+/// Returns each `X.Y.Z` version embedded in `url` at the start of a path
+/// segment or after a `-` or `_`, optionally preceded by `v`, along with the
+/// rest of `url` following the version.
+fn url_versions(url: &str) -> Vec<(&str, &str)> {
+    let bytes = url.as_bytes();
+    let is_sep = |i: usize| matches!(bytes[i], b'/' | b'-' | b'_');
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == start {
+            i += 1;
+            continue;
+        }
+
+        // Require a boundary before the version: a separator or `v` after one.
+        let bounded = start == 0
+            || is_sep(start - 1)
+            || (bytes[start - 1] == b'v' && (start == 1 || is_sep(start - 2)));
+        if !bounded {
+            continue;
+        }
+
+        // Scan two more dot-separated numbers.
+        let mut end = i;
+        let mut parts = 1;
+        while parts < 3 && end + 1 < bytes.len() && bytes[end] == b'.' {
+            let num = end + 1;
+            let mut j = num;
+            while j < bytes.len() && bytes[j].is_ascii_digit() {
+                j += 1;
+            }
+            if j == num {
+                break;
+            }
+            end = j;
+            parts += 1;
+        }
+        let followed_by_digit = |j: usize| j < bytes.len() && bytes[j].is_ascii_digit();
+        if parts == 3 && !(bytes.get(end) == Some(&b'.') && followed_by_digit(end + 1)) {
+            found.push((&url[start..end], &url[end..]));
+        }
+        i = end;
+    }
+    found
+}
+
+/// Returns the type and name of `purl`, omitting its namespace, version,
+/// qualifiers, and subpath. Returns `None` if `purl` is not a purl.
+fn purl_type_and_name(purl: &str) -> Option<(&str, &str)> {
+    let rest = purl.strip_prefix("pkg:")?.trim_start_matches('/');
+    let end = rest.find(['@', '?', '#']).unwrap_or(rest.len());
+    let (kind, path) = rest[..end].split_once('/')?;
+    let name = path.trim_end_matches('/').rsplit('/').next()?;
+    Some((kind, name))
+}
+
+/// Top-level directories of common file systems, used by [`looks_absolute`].
+const SYSTEM_DIRS: &[&str] = &[
+    "Users", "Volumes", "home", "mnt", "opt", "private", "root", "srv", "tmp", "usr", "var",
+];
+
+/// Returns true if `glob` looks like an absolute file system path. See
+/// [`Distribution::suspicious_ignore_globs`].
+fn looks_absolute(glob: &str) -> bool {
+    let bytes = glob.as_bytes();
+    if bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return matches!(bytes[2], b'/' | b'\\');
+    }
+    if glob.starts_with("~/") {
+        return true;
+    }
+    let Some(rest) = glob.strip_prefix('/') else {
+        return false;
+    };
+    match rest.split_once('/') {
+        Some((first, next)) => !next.is_empty() && SYSTEM_DIRS.contains(&first),
+        None => false,
+    }
+}
+
+/// A version range clause: an operator, a version, and whether the version
+/// was specified with no minor part.
+type RangeClause = (&'static str, Version, bool);
+
+/// Parses version range clause `clause` into an operator, a version, and
+/// the number of version parts it specifies. A version with no operator is
+/// a minimum version, and so gets `>=`. Versions missing minor or patch
+/// parts are padded with zeros. Returns `None` if the version cannot be
+/// parsed.
+fn parse_clause(clause: &str) -> Option<(&'static str, Version, usize)> {
+    let clause = clause.trim();
+    let (op, ver) = ["==", "!=", ">=", "<=", ">", "<"]
+        .into_iter()
+        .find_map(|op| clause.strip_prefix(op).map(|v| (op, v.trim())))
+        .unwrap_or((">=", clause));
+    let core = ver.split(['-', '+']).next().unwrap_or_default();
+    let parts = core.matches('.').count() + 1;
+    let padded = match parts {
+        1 => ver.replacen(core, &format!("{core}.0.0"), 1),
+        2 => ver.replacen(core, &format!("{core}.0"), 1),
+        _ => ver.to_string(),
+    };
+    Some((op, Version::parse(&padded).ok()?, parts))
+}
+
+/// Parses the comma-delimited clauses of version range `range` into
+/// [`RangeClause`]s as by [`parse_clause`]. Returns `None` if any version
+/// cannot be parsed.
+fn range_clauses(range: &str) -> Option<Vec<RangeClause>> {
+    range
+        .split(',')
+        .map(|clause| parse_clause(clause).map(|(op, ver, parts)| (op, ver, parts == 1)))
+        .collect()
+}
+
+/// Converts version range `range` into a [`VersionReq`] with a comparator
+/// for each comma-delimited clause parsed by [`parse_clause`]; `==`
+/// becomes `=`. A version missing minor or patch parts compares only the
+/// parts it specifies, so `<= 12` matches 12.1 and `> 12` matches 13.0
+/// but not 12.1, just as [`Postgres::supported_majors`] compares major
+/// versions. Build metadata is dropped. Returns `None` if a clause uses
+/// `!=`, which [`VersionReq`] cannot represent, specifies a pre-release on
+/// a partial version, or cannot be parsed.
+fn range_req(range: &str) -> Option<VersionReq> {
+    let comparators = range
+        .split(',')
+        .map(|clause| {
+            let (op, version, parts) = parse_clause(clause)?;
+            let op = match op {
+                "==" => semver::Op::Exact,
+                ">=" => semver::Op::GreaterEq,
+                "<=" => semver::Op::LessEq,
+                ">" => semver::Op::Greater,
+                "<" => semver::Op::Less,
+                _ => return None,
+            };
+            if parts < 3 && !version.pre.is_empty() {
+                return None;
+            }
+            Some(semver::Comparator {
+                op,
+                major: version.major,
+                minor: (parts > 1).then_some(version.minor),
+                patch: (parts > 2).then_some(version.patch),
+                pre: version.pre,
+            })
+        })
+        .collect::<Option<_>>()?;
+    Some(VersionReq { comparators })
+}
+
+/// Returns true if any version with major version `major` satisfies all of
+/// `clauses`. See [`Postgres::supported_majors`].
+fn major_satisfies(major: u32, clauses: &[RangeClause]) -> bool {
+    let major = u64::from(major);
+
+    // Track the bounds of the matching versions and whether each is
+    // inclusive, starting with the lowest pre-release of the major version
+    // up to the lowest pre-release of the next major version.
+    let lowest = |major| Version::parse(&format!("{major}.0.0-0")).unwrap();
+    let mut lower = (lowest(major), true);
+    let mut upper = (lowest(major + 1), false);
+    for (op, version, major_only) in clauses {
+        if *major_only {
+            let ok = match *op {
+                "==" => major == version.major,
+                "!=" => major != version.major,
+                ">=" => major >= version.major,
+                "<=" => major <= version.major,
+                ">" => major > version.major,
+                _ => major < version.major,
+            };
+            if !ok {
+                return false;
+            }
+            continue;
+        }
+
+        let (lo, hi) = match *op {
+            "==" => (Some(true), Some(true)),
+            "!=" => (None, None),
+            ">=" => (Some(true), None),
+            ">" => (Some(false), None),
+            "<=" => (None, Some(true)),
+            _ => (None, Some(false)),
+        };
+        if let Some(inclusive) = lo {
+            if *version > lower.0 || (*version == lower.0 && !inclusive) {
+                lower = (version.clone(), inclusive);
+            }
+        }
+        if let Some(inclusive) = hi {
+            if *version < upper.0 || (*version == upper.0 && !inclusive) {
+                upper = (version.clone(), inclusive);
+            }
+        }
+    }
+
+    lower.0 < upper.0 || (lower.0 == upper.0 && lower.1 && upper.1)
+}
+
+/// Commonly-used Postgres build options, such as the `--with` options to
+/// `configure`, for use with [`Postgres::unknown_with_options`].
+pub const POSTGRES_WITH_OPTIONS: &[&str] = &[
+    "bonjour", "gssapi", "icu", "ldap", "libcurl", "liburing", "llvm", "lz4", "numa", "pam",
+    "perl", "python", "readline", "selinux", "ssl", "systemd", "tcl", "uuid", "xml", "xslt",
+    "zlib", "zstd",
+];
+
+/// The name and `doc` path of documented items of one kind, as returned by
+/// [`Distribution::docs_by_kind`].
+pub type DocPaths<'a> = Vec<(&'a str, &'a RelativePath)>;
+
 /// Represents Postgres requirements under `postgres` in [`Dependencies`].
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct Postgres {
@@ -371,7 +1022,7 @@ pub struct Postgres {
     with: Option<Vec<String>>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Postgres {
@@ -385,9 +1036,60 @@ impl Postgres {
         self.with.as_deref()
     }
 
+    /// Returns the `with` entries not in `known`, in order, to catch typos
+    /// like `xlm` for `xml`. Pass [`POSTGRES_WITH_OPTIONS`] to check against
+    /// a curated list of common options. The spec allows any `with` value,
+    /// so this is a lint rather than a validation error.
+    pub fn unknown_with_options(&self, known: &[&str]) -> Vec<&str> {
+        self.with
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|opt| !known.contains(opt))
+            .collect()
+    }
+
+    /// Parses the Postgres version range into a [`VersionReq`] just as a
+    /// [`VersionRange`] converts into one, so a version with no operator is
+    /// a minimum version, a range of `0` matches any version, and a version
+    /// with no minor part compares only major versions, so `<= 12` matches
+    /// 12.1. Returns an error if the range cannot be parsed or uses `!=`,
+    /// which [`VersionReq`] cannot represent.
+    pub fn parsed_version(&self) -> Result<VersionReq, Error> {
+        let range = self.version.trim();
+        if range == "0" {
+            return Ok(VersionReq::STAR);
+        }
+        range_req(range).ok_or_else(|| Error::Invalid("postgres version", 2, Value::from(range)))
+    }
+
+    /// Returns the Postgres major versions in `known_majors` that satisfy
+    /// the Postgres version range, in the same order. A major version
+    /// satisfies the range if any release of that major version does, so
+    /// `>= 14.0, < 18.1` includes major version 18. Comparisons against a
+    /// version with no minor part compare only major versions, so `< 18`
+    /// excludes major version 18, `> 17` excludes major version 17, and
+    /// `!= 15` excludes major version 15. A range of `0` includes all
+    /// known major versions. Returns an error if the range cannot be
+    /// parsed.
+    pub fn supported_majors(&self, known_majors: &[u32]) -> Result<Vec<u32>, Error> {
+        let range = self.version.trim();
+        if range == "0" {
+            return Ok(known_majors.to_vec());
+        }
+
+        let clauses = range_clauses(range)
+            .ok_or_else(|| Error::Invalid("postgres version", 2, Value::from(range)))?;
+        Ok(known_majors
+            .iter()
+            .copied()
+            .filter(|&major| major_satisfies(major, &clauses))
+            .collect())
+    }
+
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
@@ -395,6 +1097,7 @@ impl Postgres {
 /// Represents the name of a build pipeline under `pipeline` in
 /// [`Dependencies`].
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[non_exhaustive]
 pub enum Pipeline {
     /// PGXS
     #[serde(rename = "pgxs")]
@@ -411,6 +1114,11 @@ pub enum Pipeline {
     /// cmake
     #[serde(rename = "cmake")]
     Cmake,
+    /// Holds an unknown pipeline, such as one defined by a later version of
+    /// the spec. Deserialization without validation accepts any string here;
+    /// validation rejects unknown pipelines.
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl std::fmt::Display for Pipeline {
@@ -422,12 +1130,13 @@ impl std::fmt::Display for Pipeline {
             Pipeline::Pgrx => write!(f, "pgrx"),
             Pipeline::Autoconf => write!(f, "autoconf"),
             Pipeline::Cmake => write!(f, "cmake"),
+            Pipeline::Other(s) => write!(f, "{s}"),
         }
     }
 }
 
 /// Defines a version range for [`Phase`] dependencies.
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 #[serde(untagged)]
 pub enum VersionRange {
     /// Represents `0` as a shorthand for "no specific version".
@@ -436,6 +1145,99 @@ pub enum VersionRange {
     String(String),
 }
 
+impl VersionRange {
+    /// Returns a normalized copy of the VersionRange. Both the integer `0`
+    /// and the string `"0"` mean "any version", so both normalize to the
+    /// canonical form, [`VersionRange::Integer(0)`](VersionRange::Integer),
+    /// which serializes as `0`. All other values are returned unchanged.
+    ///
+    /// Deserialization preserves the original form so that round-tripping
+    /// `META.json` data produces the same JSON; call this method to compare
+    /// or store ranges consistently.
+    pub fn normalized(&self) -> VersionRange {
+        match self {
+            VersionRange::String(s) if s == "0" => VersionRange::Integer(0),
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns true if at least one version satisfies both the VersionRange
+    /// and `other`. A range of `0` or `"0"` includes all versions, and so
+    /// overlaps every range. Versions missing minor or patch parts are
+    /// padded with zeros, so `< 2` means `< 2.0.0`. Two distinct versions
+    /// are assumed to have others between them, so `> 1.0.0, < 1.0.1`
+    /// overlaps itself. Returns an error if either range cannot be parsed.
+    pub fn overlaps(&self, other: &VersionRange) -> Result<bool, Error> {
+        let (Some(mine), Some(theirs)) = (self.clauses()?, other.clauses()?) else {
+            return Ok(true);
+        };
+
+        // Narrow the bounds and track whether each is inclusive. None means
+        // unbounded.
+        let mut lower: Option<(&Version, bool)> = None;
+        let mut upper: Option<(&Version, bool)> = None;
+        let mut excluded = Vec::new();
+        for (op, version, _) in mine.iter().chain(theirs.iter()) {
+            let (lo, hi) = match *op {
+                "==" => (Some(true), Some(true)),
+                "!=" => {
+                    excluded.push(version);
+                    continue;
+                }
+                ">=" => (Some(true), None),
+                ">" => (Some(false), None),
+                "<=" => (None, Some(true)),
+                _ => (None, Some(false)),
+            };
+            if let Some(inclusive) = lo {
+                if lower.is_none_or(|(v, inc)| version > v || (version == v && inc && !inclusive)) {
+                    lower = Some((version, inclusive));
+                }
+            }
+            if let Some(inclusive) = hi {
+                if upper.is_none_or(|(v, inc)| version < v || (version == v && inc && !inclusive)) {
+                    upper = Some((version, inclusive));
+                }
+            }
+        }
+
+        let (Some((lo, lo_inc)), Some((hi, hi_inc))) = (lower, upper) else {
+            return Ok(true);
+        };
+        Ok(lo < hi || (lo == hi && lo_inc && hi_inc && !excluded.contains(&lo)))
+    }
+
+    /// Parses the VersionRange into clauses as by [`range_clauses`].
+    /// Returns `None` if the range includes all versions.
+    fn clauses(&self) -> Result<Option<Vec<RangeClause>>, Error> {
+        let range = match self {
+            VersionRange::Integer(0) => return Ok(None),
+            VersionRange::Integer(int) => {
+                return Err(Error::Invalid("version range", 2, Value::from(*int)))
+            }
+            VersionRange::String(str) => str.trim(),
+        };
+        if range == "0" {
+            return Ok(None);
+        }
+        range_clauses(range)
+            .map(Some)
+            .ok_or_else(|| Error::Invalid("version range", 2, Value::from(range)))
+    }
+
+    /// Returns true if any version in the range carries SemVer build
+    /// metadata, such as `1.2.3+meta`.
+    fn has_build_metadata(&self) -> bool {
+        let VersionRange::String(range) = self else {
+            return false;
+        };
+        range.split(',').any(|part| {
+            let version = part.trim().trim_start_matches(['<', '>', '=', '!']).trim();
+            Version::parse(version).is_ok_and(|v| !v.build.is_empty())
+        })
+    }
+}
+
 impl std::fmt::Display for VersionRange {
     /// fmt writes the sting representation of the Pipeline to f.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -446,46 +1248,149 @@ impl std::fmt::Display for VersionRange {
     }
 }
 
+impl TryFrom<&VersionRange> for VersionReq {
+    type Error = Error;
+    /// Converts a [`VersionRange`] into a [`VersionReq`]. A range of `0` or
+    /// `"0"` converts to [`VersionReq::STAR`]. Each comma-delimited clause
+    /// becomes a comparator: a version with no operator is a minimum
+    /// version, and so becomes `>=`, and `==` becomes `=`. Unlike
+    /// [`overlaps`](VersionRange::overlaps), versions missing minor or
+    /// patch parts compare only the parts they specify, so `<= 12` becomes
+    /// `<=12` and matches 12.1, while `> 2` matches 3.0 but not 2.1.
+    ///
+    /// Some ranges cannot be represented: [`VersionReq`] has no `!=`
+    /// operator, its comparators drop build metadata, which SemVer ignores
+    /// for precedence anyway, and its partial versions cannot have
+    /// pre-releases. Returns an error for a range that uses `!=`, a
+    /// pre-release on a partial version, or cannot be parsed. Note too that
+    /// a [`VersionReq`] only matches a pre-release version if one of its
+    /// comparators has a pre-release on the same major, minor, and patch
+    /// version.
+    fn try_from(range: &VersionRange) -> Result<Self, Self::Error> {
+        let range = match range {
+            VersionRange::Integer(0) => return Ok(VersionReq::STAR),
+            VersionRange::Integer(int) => {
+                return Err(Error::Invalid("version range", 2, Value::from(*int)))
+            }
+            VersionRange::String(str) => str.trim(),
+        };
+        if range == "0" {
+            return Ok(VersionReq::STAR);
+        }
+        range_req(range).ok_or_else(|| Error::Invalid("version range", 2, Value::from(range)))
+    }
+}
+
+impl From<&VersionReq> for VersionRange {
+    /// Converts a [`VersionReq`] into a [`VersionRange`].
+    /// [`VersionReq::STAR`] converts to `0`. Comparators that the range
+    /// syntax lacks, such as `~`, `^`, and `*`, and comparators with
+    /// partial versions, such as `=1.2`, convert to equivalent lower and
+    /// upper bounds, so `^1.2` becomes `>=1.2.0, <2.0.0`.
+    fn from(req: &VersionReq) -> Self {
+        if req.comparators.is_empty() {
+            return VersionRange::Integer(0);
+        }
+        let clauses: Vec<String> = req.comparators.iter().map(comparator_clause).collect();
+        VersionRange::String(clauses.join(", "))
+    }
+}
+
+/// Converts `cmp` into a clause in the [`VersionRange`] syntax, replacing
+/// partial versions and operators the syntax lacks with bounds.
+fn comparator_clause(cmp: &semver::Comparator) -> String {
+    use semver::Op;
+    let (major, minor, patch) = (cmp.major, cmp.minor, cmp.patch);
+
+    // The lowest matching version and the lowest version above every
+    // version matched by the partial version.
+    let lower = match (minor, patch) {
+        (Some(minor), Some(patch)) => {
+            let mut v = Version::new(major, minor, patch);
+            v.pre = cmp.pre.clone();
+            v
+        }
+        (Some(minor), None) => Version::new(major, minor, 0),
+        _ => Version::new(major, 0, 0),
+    };
+    let next = |minor: Option<u64>| match minor {
+        Some(minor) => Version::new(major, minor + 1, 0),
+        None => Version::new(major + 1, 0, 0),
+    };
+    let bounded = |upper: Version| format!(">={lower}, <{upper}");
+
+    match cmp.op {
+        Op::Exact | Op::Wildcard if patch.is_none() => bounded(next(minor)),
+        Op::Exact => format!("=={lower}"),
+        Op::Greater if patch.is_none() => format!(">={}", next(minor)),
+        Op::Greater => format!(">{lower}"),
+        Op::LessEq if patch.is_none() => format!("<{}", next(minor)),
+        Op::LessEq => format!("<={lower}"),
+        Op::Less => format!("<{lower}"),
+        Op::Tilde => bounded(next(minor)),
+        Op::Caret => bounded(match (major, minor, patch) {
+            (0, Some(0), Some(patch)) => Version::new(0, 0, patch + 1),
+            (0, Some(minor), _) => Version::new(0, minor + 1, 0),
+            _ => Version::new(major + 1, 0, 0),
+        }),
+        // Op::GreaterEq and any operators added to semver in the future.
+        _ => format!(">={lower}"),
+    }
+}
+
 /// Defines the relationships for a build phase in [`Packages`].
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct Phase {
     #[serde(skip_serializing_if = "Option::is_none")]
-    requires: Option<HashMap<String, VersionRange>>,
+    requires: Option<BTreeMap<String, VersionRange>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    recommends: Option<HashMap<String, VersionRange>>,
+    recommends: Option<BTreeMap<String, VersionRange>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    suggests: Option<HashMap<String, VersionRange>>,
+    suggests: Option<BTreeMap<String, VersionRange>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    conflicts: Option<HashMap<String, VersionRange>>,
+    conflicts: Option<BTreeMap<String, VersionRange>>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Phase {
     /// Borrows the Phase requires object.
-    pub fn requires(&self) -> Option<&HashMap<String, VersionRange>> {
+    pub fn requires(&self) -> Option<&BTreeMap<String, VersionRange>> {
         self.requires.as_ref()
     }
 
     /// Borrows the Phase recommends object.
-    pub fn recommends(&self) -> Option<&HashMap<String, VersionRange>> {
+    pub fn recommends(&self) -> Option<&BTreeMap<String, VersionRange>> {
         self.recommends.as_ref()
     }
 
     /// Borrows the Phase suggests object.
-    pub fn suggests(&self) -> Option<&HashMap<String, VersionRange>> {
+    pub fn suggests(&self) -> Option<&BTreeMap<String, VersionRange>> {
         self.suggests.as_ref()
     }
 
     /// Borrows the Phase conflicts object.
-    pub fn conflicts(&self) -> Option<&HashMap<String, VersionRange>> {
+    pub fn conflicts(&self) -> Option<&BTreeMap<String, VersionRange>> {
         self.conflicts.as_ref()
     }
 
+    /// Returns the name and packages of each relationship in the Phase.
+    fn relationships(&self) -> Vec<(&'static str, &BTreeMap<String, VersionRange>)> {
+        [
+            ("requires", self.requires()),
+            ("recommends", self.recommends()),
+            ("suggests", self.suggests()),
+            ("conflicts", self.conflicts()),
+        ]
+        .into_iter()
+        .filter_map(|(name, pkgs)| pkgs.map(|p| (name, p)))
+        .collect()
+    }
+
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
@@ -506,7 +1411,7 @@ pub struct Packages {
     develop: Option<Phase>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Packages {
@@ -535,9 +1440,23 @@ impl Packages {
         self.develop.as_ref()
     }
 
+    /// Returns the name and object of each Phase in the Packages.
+    fn phases(&self) -> Vec<(&'static str, &Phase)> {
+        [
+            ("configure", self.configure()),
+            ("build", self.build()),
+            ("test", self.test()),
+            ("run", self.run()),
+            ("develop", self.develop()),
+        ]
+        .into_iter()
+        .filter_map(|(name, phase)| phase.map(|p| (name, p)))
+        .collect()
+    }
+
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
@@ -550,7 +1469,7 @@ pub struct Variations {
     dependencies: Dependencies,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Variations {
@@ -566,7 +1485,7 @@ impl Variations {
 
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
@@ -586,7 +1505,7 @@ pub struct Dependencies {
     variations: Option<Vec<Variations>>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Dependencies {
@@ -615,13 +1534,81 @@ impl Dependencies {
         self.variations.as_deref()
     }
 
+    /// Returns the name and object of each package Phase in the
+    /// Dependencies and its variations.
+    fn all_phases(&self) -> Vec<(&'static str, &Phase)> {
+        let mut phases = self.packages().map(Packages::phases).unwrap_or_default();
+        for variation in self.variations().unwrap_or_default() {
+            phases.extend(variation.dependencies().all_phases());
+        }
+        phases
+    }
+
+    /// Returns the indexes of variations whose `where.platforms` share no
+    /// platform with the Dependencies `platforms`, and so can never apply.
+    /// Platforms are compared by OS name only, and `any` matches every
+    /// platform, so that, for example, `linux-arm64` intersects `linux`.
+    /// Returns an empty vector if the Dependencies declare no platforms.
+    pub fn unreachable_variations(&self) -> Vec<usize> {
+        let Some(base) = self.platforms() else {
+            return Vec::new();
+        };
+        self.variations()
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .filter(|(_, var)| {
+                var.wheres().platforms().is_some_and(|wheres| {
+                    !wheres
+                        .iter()
+                        .any(|w| base.iter().any(|b| platforms_intersect(b, w)))
+                })
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the distinct purl types, such as `pgxn`, `postgres`, and
+    /// `generic`, of all package dependencies in all phases of the
+    /// Dependencies and its variations, sorted. Keys that are not purls are
+    /// omitted.
+    pub fn dependency_sources(&self) -> Vec<String> {
+        let mut sources: Vec<String> = self
+            .all_phases()
+            .into_iter()
+            .flat_map(|(_, phase)| phase.relationships())
+            .flat_map(|(_, pkgs)| pkgs.keys())
+            .filter_map(|purl| purl_type_and_name(purl).map(|(kind, _)| kind.to_string()))
+            .collect();
+        sources.sort();
+        sources.dedup();
+        sources
+    }
+
+    /// Returns true if any package version range in the Dependencies or its
+    /// variations carries SemVer build metadata.
+    fn has_build_metadata(&self) -> bool {
+        self.all_phases().into_iter().any(|(_, phase)| {
+            phase
+                .relationships()
+                .into_iter()
+                .any(|(_, rels)| rels.values().any(VersionRange::has_build_metadata))
+        })
+    }
+
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
 
+/// Returns true if platforms `a` and `b` have the same OS name or either is
+/// `any`.
+fn platforms_intersect(a: &str, b: &str) -> bool {
+    a == "any" || b == "any" || a.split('-').next() == b.split('-').next()
+}
+
 /// Defines the badges under `badges` in [`Resources`].
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct Badge {
@@ -631,7 +1618,7 @@ pub struct Badge {
     url: Option<String>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Badge {
@@ -652,7 +1639,7 @@ impl Badge {
 
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
@@ -674,7 +1661,7 @@ pub struct Resources {
     badges: Option<Vec<Badge>>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Resources {
@@ -708,13 +1695,94 @@ impl Resources {
         self.badges.as_deref()
     }
 
+    /// Borrows the badges that are not served securely. A badge is insecure
+    /// if its `src` image uses any scheme other than `https`, or if its
+    /// `url` link uses `http`. Links using other schemes, such as `mailto`,
+    /// are not considered insecure. Schemes are compared
+    /// case-insensitively.
+    pub fn insecure_badges(&self) -> Vec<&Badge> {
+        let scheme_is = |uri: &str, scheme: &str| {
+            uri.split_once(':')
+                .is_some_and(|(s, _)| s.eq_ignore_ascii_case(scheme))
+        };
+        self.badges()
+            .unwrap_or_default()
+            .iter()
+            .filter(|b| {
+                !scheme_is(b.src(), "https") || b.url().is_some_and(|u| scheme_is(u, "http"))
+            })
+            .collect()
+    }
+
+    /// Parses the Resources repository URL into a [`RepoInfo`] if it points
+    /// to a repository on a recognized forge: `github.com`, `gitlab.com`, or
+    /// `codeberg.org`. The URL may use any scheme, include user info and a
+    /// `www.` host prefix, and end in `.git`. Paths after the repository
+    /// name, such as `/tree/main`, are ignored. GitLab owners may include
+    /// subgroups, as in `group/subgroup`. Returns `None` if there is no
+    /// repository or it is not on a recognized forge.
+    pub fn repository_info(&self) -> Option<RepoInfo> {
+        let url = self.repository()?;
+        let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?.to_ascii_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        let path = path.split(['?', '#']).next()?;
+
+        let segments: Vec<&str> = match host {
+            "github.com" | "codeberg.org" => path.split('/').take(2).collect(),
+            // GitLab separates the project path from other pages with `/-/`.
+            "gitlab.com" => path.split("/-/").next()?.split('/').collect(),
+            _ => return None,
+        };
+        let segments: Vec<&str> = segments.into_iter().filter(|s| !s.is_empty()).collect();
+        let (name, owner) = segments.split_last()?;
+        let name = name.strip_suffix(".git").unwrap_or(name);
+        if owner.is_empty() || name.is_empty() {
+            return None;
+        }
+
+        Some(RepoInfo {
+            host: host.to_string(),
+            owner: owner.join("/"),
+            name: name.to_string(),
+        })
+    }
+
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
 
+/// The host, owner, and name of a forge repository, returned by
+/// [`Resources::repository_info`].
+#[derive(Serialize, PartialEq, Debug)]
+pub struct RepoInfo {
+    host: String,
+    owner: String,
+    name: String,
+}
+
+impl RepoInfo {
+    /// Borrows the RepoInfo host, such as `github.com`.
+    pub fn host(&self) -> &str {
+        self.host.as_str()
+    }
+
+    /// Borrows the RepoInfo owner, the user or organization that owns the
+    /// repository.
+    pub fn owner(&self) -> &str {
+        self.owner.as_str()
+    }
+
+    /// Borrows the RepoInfo repository name.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
 /// Defines the artifacts in the array under `artifacts` in [`Distribution`].
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct Artifact {
@@ -729,7 +1797,7 @@ pub struct Artifact {
     sha512: Option<String>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
 }
 
 impl Artifact {
@@ -758,13 +1826,83 @@ impl Artifact {
         self.sha512.as_deref()
     }
 
+    /// Validates the Artifact platform against the v2 `platform` schema.
+    /// Returns an error if the platform is not well-formed. Returns `Ok` if
+    /// the Artifact has no platform.
+    pub fn validate_platform(&self) -> Result<(), Error> {
+        let Some(platform) = self.platform() else {
+            return Ok(());
+        };
+        let mut validator = crate::valid::Validator::new();
+        validator.validate_version_schema(&Value::from(platform), 2, "platform.schema.json")
+    }
+
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
 }
 
+/// Returns a skeleton v2 `META.json` object for a distribution named `name`,
+/// with placeholder values for the abstract, version, license, and
+/// maintainer, and a single extension also named `name`. The skeleton is
+/// valid v2 metadata, provided `name` is a valid distribution name, and is
+/// intended as a starting point for a new `META.json` file.
+///
+/// ``` rust
+/// use pgxn_meta::{dist::template, valid::Validator};
+///
+/// let meta = template("widget");
+/// assert_eq!("widget", meta["name"]);
+/// assert_eq!(2, Validator::new().validate(&meta).unwrap());
+/// ```
+pub fn template(name: &str) -> Value {
+    serde_json::json!({
+        "name": name,
+        "abstract": format!("TODO: Describe {name} in a single sentence"),
+        "version": "0.1.0",
+        "maintainers": [{"name": "TODO: Your Name", "email": "todo@example.com"}],
+        "license": "PostgreSQL",
+        "contents": {
+            "extensions": {
+                name: {
+                    "sql": format!("sql/{name}.sql"),
+                    "control": format!("{name}.control"),
+                },
+            },
+        },
+        "meta-spec": {"version": "2.0.0"},
+    })
+}
+
+/// Tallies how many of `dists` declare each classification category.
+/// Useful for building a category index.
+///
+/// ``` rust
+/// use serde_json::json;
+/// use pgxn_meta::dist::{category_histogram, template, Distribution};
+///
+/// let mut meta = template("widget");
+/// meta["classifications"] = json!({"categories": ["Search"]});
+/// let dist = Distribution::try_from(meta).unwrap();
+/// let hist = category_histogram([&dist]);
+/// assert_eq!(Some(&1), hist.get("Search"));
+/// ```
+pub fn category_histogram<'a, I>(dists: I) -> BTreeMap<String, usize>
+where
+    I: IntoIterator<Item = &'a Distribution>,
+{
+    let mut hist = BTreeMap::new();
+    for dist in dists {
+        let categories = dist.classifications().and_then(Classifications::categories);
+        for category in categories.into_iter().flatten() {
+            *hist.entry(category.clone()).or_insert(0) += 1;
+        }
+    }
+    hist
+}
+
 /**
 Represents the `META.json` data from a PGXN distribution.
 
@@ -807,21 +1945,64 @@ pub struct Distribution {
     artifacts: Option<Vec<Artifact>>,
     #[serde(flatten)]
     #[serde(deserialize_with = "deserialize_custom_properties")]
-    custom_props: HashMap<String, Value>,
+    custom_props: BTreeMap<String, Value>,
+}
+
+/// Returns the JSON Pointer path to the first null value in `val`, or `None`
+/// if it contains no nulls. See [`Distribution::to_toml`].
+#[cfg(feature = "toml")]
+fn null_path(val: &Value) -> Option<String> {
+    let escape = |key: &str| key.replace('~', "~0").replace('/', "~1");
+    match val {
+        Value::Null => Some(String::new()),
+        Value::Array(list) => list
+            .iter()
+            .enumerate()
+            .find_map(|(i, v)| null_path(v).map(|p| format!("/{i}{p}"))),
+        Value::Object(obj) => obj
+            .iter()
+            .find_map(|(k, v)| null_path(v).map(|p| format!("/{}{p}", escape(k)))),
+        _ => None,
+    }
+}
+
+/// Compares maintainers `a` and `b` by name and then by email.
+fn cmp_maintainers(a: &Maintainer, b: &Maintainer) -> std::cmp::Ordering {
+    a.name()
+        .cmp(b.name())
+        .then_with(|| a.email().cmp(&b.email()))
+}
+
+/// Returns true if `key` starts with `x_` or `X_`.
+fn is_custom_prop(key: &str) -> bool {
+    key.starts_with("x_") || key.starts_with("X_")
+}
+
+/// Recursively removes all keys starting with `x_` or `X_` from objects in
+/// `val`.
+fn strip_custom_props(val: &mut Value) {
+    match val {
+        Value::Object(obj) => {
+            obj.retain(|key, _| !is_custom_prop(key));
+            obj.values_mut().for_each(strip_custom_props);
+        }
+        Value::Array(vals) => vals.iter_mut().for_each(strip_custom_props),
+        _ => {}
+    }
 }
 
-/// Deserializes fields starting with `X_` or `x_` into a HashMap.
+/// Deserializes fields starting with `X_` or `x_` into a BTreeMap.
 pub fn deserialize_custom_properties<'de, D>(
     deserializer: D,
-) -> Result<HashMap<String, Value>, D::Error>
+) -> Result<BTreeMap<String, Value>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let map: HashMap<String, Value> = HashMap::deserialize(deserializer)?;
+    let map: BTreeMap<String, Value> = BTreeMap::deserialize(deserializer)?;
 
     Ok(map
         .into_iter()
-        .filter(|(key, _value)| key.starts_with("x_") || key.starts_with("X_"))
+        .filter(|(key, _value)| is_custom_prop(key))
         .collect())
 }
 
@@ -844,6 +2025,152 @@ impl Distribution {
         meta.try_into()
     }
 
+    /// Validates `meta` and converts it into a [`Distribution`], returning
+    /// the original Meta spec version (1 or 2) along with it. Useful to
+    /// determine whether the Distribution was converted from v1 metadata.
+    /// Returns an error if `meta` is invalid.
+    pub fn from_value_with_version(meta: Value) -> Result<(u8, Self), Error> {
+        let mut validator = crate::valid::Validator::new();
+        let version = validator.validate(&meta)?;
+        Ok((version, Distribution::from_version(version, meta)?))
+    }
+
+    /// Deserializes `meta` into a [`Distribution`] without validation,
+    /// tolerating unknown module types, preload values, and pipelines, such
+    /// as those defined by later versions of the spec. Returns the
+    /// Distribution along with a warning for each unknown value, which are
+    /// deserialized into the `Other` variants of [`ModuleType`],
+    /// [`Preload`], and [`Pipeline`]. v1 metadata is converted to v2. Useful
+    /// for forward-compatible, read-only tools; use the [TryFrom] traits to
+    /// validate metadata. Returns an error if `meta` cannot be deserialized.
+    pub fn from_value_lenient(meta: Value) -> Result<(Self, Vec<String>), Error> {
+        let meta = match util::get_version(&meta)? {
+            1 => v1::to_v2(&meta)?,
+            _ => meta,
+        };
+        let dist = v2::from_value(meta)?;
+
+        let mut warnings = Vec::new();
+        for (name, module) in dist.contents.modules().into_iter().flatten() {
+            if let ModuleType::Other(kind) = module.kind() {
+                warnings.push(format!("module {name}: unknown type \"{kind}\""));
+            }
+            if let Some(Preload::Other(preload)) = module.preload() {
+                warnings.push(format!("module {name}: unknown preload \"{preload}\""));
+            }
+        }
+        let mut deps: Vec<&Dependencies> = dist.dependencies().into_iter().collect();
+        while let Some(dep) = deps.pop() {
+            if let Some(Pipeline::Other(pipeline)) = dep.pipeline() {
+                warnings.push(format!("dependencies: unknown pipeline \"{pipeline}\""));
+            }
+            for variation in dep.variations().unwrap_or_default() {
+                deps.push(variation.dependencies());
+            }
+        }
+        warnings.sort();
+
+        Ok((dist, warnings))
+    }
+
+    /// Parses `yaml` into JSON and then converts it into a [`Distribution`].
+    /// Returns an error if `yaml` cannot be parsed or its content is not
+    /// valid PGXN `META.json` data. Requires the `yaml` feature.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use pgxn_meta::dist::*;
+    ///
+    /// let yaml = r#"
+    /// name: pair
+    /// abstract: A key/value pair data type
+    /// version: 0.1.8
+    /// maintainers:
+    ///   - name: theory
+    ///     email: theory@pgxn.org
+    /// license: PostgreSQL
+    /// contents:
+    ///   extensions:
+    ///     pair:
+    ///       sql: sql/pair.sql
+    ///       control: pair.control
+    /// meta-spec:
+    ///   version: 2.0.0
+    /// "#;
+    ///
+    /// let meta = Distribution::from_yaml(yaml);
+    /// assert!(meta.is_ok());
+    /// ```
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, Error> {
+        let meta: Value = serde_yaml::from_str(yaml)?;
+        meta.try_into()
+    }
+
+    /// Serializes the Distribution to TOML. Requires the `toml` feature.
+    ///
+    /// TOML cannot represent every JSON structure. It has no null value, so
+    /// returns an error naming the property path of any null, such as in a
+    /// custom property. TOML also writes the properties of each table
+    /// before its nested tables, so the output may order properties
+    /// differently than [`serde_json::to_string`] would.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use pgxn_meta::dist::*;
+    ///
+    /// let dist = Distribution::try_from(template("pair")).unwrap();
+    /// let toml = dist.to_toml().unwrap();
+    /// assert!(toml.contains("name = \"pair\""));
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String, Error> {
+        use serde::ser::Error as _;
+        let meta = serde_json::to_value(self)?;
+        if let Some(path) = null_path(&meta) {
+            let msg = format!("cannot represent null value at {path} in TOML");
+            return Err(toml::ser::Error::custom(msg).into());
+        }
+        Ok(toml::to_string(&meta)?)
+    }
+
+    /// Loads the `META.json` file from the gzipped tarball at `path` and
+    /// converts it into a [`Distribution`]. The file must be at the root of
+    /// the archive or in a single top-level directory, as in
+    /// `pair-0.1.8/META.json`; `META.json` files nested deeper are ignored.
+    /// Returns an error if the archive cannot be read, contains no such
+    /// `META.json` or more than one, or if its content is not valid PGXN
+    /// `META.json` data. Requires the `archives` feature.
+    #[cfg(feature = "archives")]
+    pub fn from_tar_gz<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        use std::path::Component;
+        let gz = flate2::read::GzDecoder::new(File::open(path)?);
+        let mut archive = tar::Archive::new(gz);
+        let mut meta: Option<Value> = None;
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?;
+            let depth = path
+                .components()
+                .filter(|c| matches!(c, Component::Normal(_)))
+                .count();
+            if depth > 2
+                || path.file_name() != Some("META.json".as_ref())
+                || !entry.header().entry_type().is_file()
+            {
+                continue;
+            }
+            if meta.is_some() {
+                return Err(Error::Param("multiple META.json files in archive"));
+            }
+            meta = Some(serde_json::from_reader(&mut entry)?);
+        }
+        meta.ok_or(Error::Param("no META.json file in archive"))?
+            .try_into()
+    }
+
     /// Borrows the Distribution name.
     pub fn name(&self) -> &str {
         self.name.as_str()
@@ -854,6 +2181,13 @@ impl Distribution {
         self.version.borrow()
     }
 
+    /// Returns the canonical `name@version` identifier for the
+    /// Distribution, such as `pair@0.1.8`, suitable for cache keys and
+    /// URLs. Use [`parse_id`] to parse it.
+    pub fn id(&self) -> String {
+        format!("{}@{}", self.name, self.version)
+    }
+
     /// Borrows the Distribution abstract.
     pub fn abs_tract(&self) -> &str {
         self.abs_tract.as_str()
@@ -864,16 +2198,68 @@ impl Distribution {
         self.description.as_deref()
     }
 
+    /// Returns true if the Distribution description is the same as its
+    /// abstract, ignoring leading and trailing whitespace. Such a
+    /// description adds no information, but is not invalid. Returns false
+    /// if the Distribution has no description.
+    pub fn description_duplicates_abstract(&self) -> bool {
+        self.description()
+            .is_some_and(|desc| desc.trim() == self.abs_tract.trim())
+    }
+
     /// Borrows the Distribution producer.
     pub fn producer(&self) -> Option<&str> {
         self.producer.as_deref()
     }
 
+    /// Heuristically splits the Distribution producer into a tool name and
+    /// optional version, for producers written by tools rather than people.
+    /// Recognizes a producer consisting of:
+    ///
+    /// *   A name and version separated by a space, as in `pgrx 0.11.4`
+    /// *   A name and version separated by a slash, as in `tool/1.2.3`
+    /// *   A single lowercase name with no version, as in `pgxn-tools`
+    ///
+    /// A name must start with an ASCII letter and contain only ASCII
+    /// letters, digits, `-`, `_`, `.`, and `:`. A version must start with an
+    /// ASCII digit, optionally preceded by `v`, and contain only ASCII
+    /// letters, digits, `.`, `-`, and `+`. Returns `None` for anything else,
+    /// including free-form human names such as `David E. Wheeler`, and when
+    /// the Distribution has no producer.
+    pub fn producer_tool(&self) -> Option<(&str, Option<&str>)> {
+        producer_tool(self.producer.as_deref()?)
+    }
+
     /// Borrows the Distribution license string.
     pub fn license(&self) -> &str {
         self.license.as_str()
     }
 
+    /// Parses the Distribution license as an [SPDX license expression] and
+    /// returns it in canonical form:
+    ///
+    /// *   License and exception identifiers use their canonical SPDX case
+    ///     and imprecise names are replaced by their SPDX identifiers, so
+    ///     `mit` becomes `MIT`
+    /// *   Operators are upper case and separated from their operands by a
+    ///     single space: `AND`, `OR`, and `WITH`
+    /// *   `WITH` binds its exception directly to the preceding license and
+    ///     is never wrapped in parentheses
+    /// *   Parentheses appear only where required by operator precedence
+    ///     (`WITH` binds tighter than `AND`, which binds tighter than `OR`),
+    ///     so `(MIT OR (PostgreSQL))` becomes `MIT OR PostgreSQL`, while
+    ///     `MIT AND (PostgreSQL OR Apache-2.0)` keeps its parentheses
+    /// *   GNU licenses use the `-only` and `-or-later` suffixes, so
+    ///     `GPL-3.0+` becomes `GPL-3.0-or-later`
+    ///
+    /// The order of operands is preserved. Returns an error if the license
+    /// cannot be parsed.
+    ///
+    /// [SPDX license expression]: https://spdx.github.io/spdx-spec/v3.0.1/annexes/spdx-license-expressions/
+    pub fn canonical_license(&self) -> Result<String, Error> {
+        canonical_license(&self.license)
+    }
+
     /// Borrows the Distribution meta spec object.
     pub fn spec(&self) -> &Spec {
         self.spec.borrow()
@@ -884,6 +2270,101 @@ impl Distribution {
         self.maintainers.borrow()
     }
 
+    /// Borrows the primary maintainer, which the spec defines as the first
+    /// maintainer. Returns `None` if there are no maintainers, which valid
+    /// metadata never allows but unvalidated deserialization may produce.
+    pub fn primary_maintainer(&self) -> Option<&Maintainer> {
+        self.maintainers.first()
+    }
+
+    /// Sorts the Distribution maintainers in place by name and then by
+    /// email. The spec treats the first maintainer as the primary
+    /// maintainer, so sorting may change which maintainer is primary. Use
+    /// [`sorted_maintainers`](Self::sorted_maintainers) to leave the stored
+    /// order intact.
+    pub fn sort_maintainers(&mut self) {
+        self.maintainers.sort_by(cmp_maintainers);
+    }
+
+    /// Borrows the Distribution maintainers sorted by name and then by
+    /// email, leaving the stored order, and therefore the primary
+    /// maintainer, intact.
+    pub fn sorted_maintainers(&self) -> Vec<&Maintainer> {
+        let mut maintainers: Vec<&Maintainer> = self.maintainers.iter().collect();
+        maintainers.sort_by(|a, b| cmp_maintainers(a, b));
+        maintainers
+    }
+
+    /// Serializes the Distribution and validates the result with a fresh
+    /// [`Validator`](crate::valid::Validator), returning the Meta spec
+    /// version, which is always 2. Use it to verify that the Distribution
+    /// remains valid after mutation, such as by
+    /// [`sort_maintainers`](Self::sort_maintainers), or after
+    /// deserialization without validation. Returns an error if the
+    /// Distribution is no longer valid.
+    pub fn revalidate(&self) -> Result<u8, Error> {
+        let meta = serde_json::to_value(self)?;
+        let mut validator = crate::valid::Validator::new();
+        validator.validate(&meta)
+    }
+
+    /// Appends `tags` to the Distribution `classifications`, skipping any
+    /// already present, then [revalidates](Self::revalidate) the
+    /// Distribution. Unlike an [RFC 7396] merge patch, which replaces the
+    /// whole array, existing tags are preserved. Returns an error and
+    /// leaves the tags unchanged if the result is invalid.
+    ///
+    ///   [RFC 7396]: https://www.rfc-editor.org/rfc/rfc7396.html
+    pub fn add_tags(&mut self, tags: &[&str]) -> Result<(), Error> {
+        self.add_classifications(tags, |c| &mut c.tags)
+    }
+
+    /// Appends `categories` to the Distribution `classifications`, skipping
+    /// any already present, then [revalidates](Self::revalidate) the
+    /// Distribution. Returns an error and leaves the categories unchanged if
+    /// the result is invalid, as when a category is not one of those
+    /// defined by the spec or the Distribution would have more than three.
+    pub fn add_categories(&mut self, categories: &[&str]) -> Result<(), Error> {
+        self.add_classifications(categories, |c| &mut c.categories)
+    }
+
+    /// Appends `terms` to the list of classifications selected by `field`,
+    /// as described by [`add_tags`](Self::add_tags), and restores the
+    /// previous classifications if the result fails to validate.
+    fn add_classifications(
+        &mut self,
+        terms: &[&str],
+        field: fn(&mut Classifications) -> &mut Option<Vec<String>>,
+    ) -> Result<(), Error> {
+        if terms.is_empty() {
+            return Ok(());
+        }
+        let created = self.classifications.is_none();
+        let classes = self.classifications.get_or_insert_with(|| Classifications {
+            tags: None,
+            categories: None,
+            custom_props: BTreeMap::new(),
+        });
+        let list = field(classes);
+        let prev = list.clone();
+        let list = list.get_or_insert_with(Vec::new);
+        for term in terms {
+            if !list.iter().any(|t| t == term) {
+                list.push(term.to_string());
+            }
+        }
+
+        if let Err(e) = self.revalidate() {
+            match self.classifications.as_mut() {
+                Some(_) if created => self.classifications = None,
+                Some(classes) => *field(classes) = prev,
+                None => {}
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
     /// Borrows the Dependencies classifications object.
     pub fn classifications(&self) -> Option<&Classifications> {
         self.classifications.as_ref()
@@ -916,9 +2397,853 @@ impl Distribution {
 
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.custom_props.borrow()
     }
+
+    /// Returns true if the Distribution was converted from v1 metadata by
+    /// [`to_v2_marked`].
+    pub fn converted_from_v1(&self) -> bool {
+        self.custom_props.get(v1::FROM_V1_PROPERTY) == Some(&Value::Bool(true))
+    }
+
+    /// Returns the name and object of the Distribution's primary extension:
+    /// the extension whose name equals the Distribution name if there is
+    /// one, otherwise the sole extension if the Distribution contains exactly
+    /// one. Returns `None` if the Distribution contains no extensions or
+    /// multiple extensions, none of which has the Distribution name.
+    pub fn primary_extension(&self) -> Option<(&str, &Extension)> {
+        let exts = self.contents.extensions()?;
+        if let Some((name, ext)) = exts.get_key_value(self.name()) {
+            return Some((name.as_str(), ext));
+        }
+        if exts.len() == 1 {
+            return exts.iter().next().map(|(name, ext)| (name.as_str(), ext));
+        }
+        None
+    }
+
+    /// Returns a [`DistributionSummary`] projecting the name, version,
+    /// abstract, license, maintainer names, tags, and categories of the
+    /// Distribution.
+    pub fn summary(&self) -> DistributionSummary {
+        let class = self.classifications();
+        DistributionSummary {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            abs_tract: self.abs_tract.clone(),
+            license: self.license.clone(),
+            maintainers: self.maintainers.iter().map(|m| m.name.clone()).collect(),
+            tags: class.and_then(|c| c.tags.clone()),
+            categories: class.and_then(|c| c.categories.clone()),
+        }
+    }
+
+    /// Returns a `SHA512SUMS`-style manifest of the Distribution artifacts,
+    /// with one `<digest>  <url>` line for each artifact. Uses the SHA-512
+    /// digest when present, and otherwise the SHA-256 digest. Returns an
+    /// error if an artifact has neither digest, and an empty string if the
+    /// Distribution has no artifacts.
+    pub fn artifact_manifest(&self) -> Result<String, Error> {
+        let mut manifest = String::new();
+        for artifact in self.artifacts().unwrap_or_default() {
+            let digest = artifact
+                .sha512()
+                .or(artifact.sha256())
+                .ok_or(Error::Missing("artifact sha512 or sha256"))?;
+            manifest.push_str(&format!("{digest}  {}\n", artifact.url()));
+        }
+        Ok(manifest)
+    }
+
+    /// Borrows the URLs of artifacts with an embedded version that does not
+    /// match the Distribution version. Versions are recognized as `X.Y.Z`
+    /// at the start of a path segment or after a `-` or `_`, optionally
+    /// preceded by `v`, as in `.../v1.2.3/pair-1.2.3.zip`. An artifact is a
+    /// mismatch if the major, minor, or patch of any version in its URL
+    /// differs from the Distribution version, or, if the Distribution
+    /// version has a pre-release, if any version in its URL is not followed
+    /// by `-` and that pre-release. Artifacts with no recognizable version
+    /// in their URLs are ignored.
+    pub fn artifact_version_mismatches(&self) -> Vec<&str> {
+        let version = self.version();
+        let core = format!("{}.{}.{}", version.major, version.minor, version.patch);
+        let pre = format!("-{}", version.pre);
+        self.artifacts()
+            .unwrap_or_default()
+            .iter()
+            .map(Artifact::url)
+            .filter(|url| {
+                url_versions(url).into_iter().any(|(found, rest)| {
+                    found != core || (!version.pre.is_empty() && !rest.starts_with(&pre))
+                })
+            })
+            .collect()
+    }
+
+    /// Returns true if the Distribution version is a pre-release, such as
+    /// `1.2.1-beta1`, and it lists any `artifacts`. Published artifacts
+    /// usually accompany stable releases, so this combination may be a
+    /// mistake. This is a policy lint; the spec allows it.
+    pub fn prerelease_with_artifacts(&self) -> bool {
+        !self.version.pre.is_empty() && self.artifacts.as_ref().is_some_and(|a| !a.is_empty())
+    }
+
+    /// Returns the names of maintainers that have neither an email address
+    /// nor a URL. The v2 spec requires one or the other, but Distributions
+    /// converted from v1 metadata or deserialized without validation may
+    /// lack both.
+    pub fn unreachable_maintainers(&self) -> Vec<&str> {
+        self.maintainers
+            .iter()
+            .filter(|m| m.email().is_none() && m.url().is_none())
+            .map(Maintainer::name)
+            .collect()
+    }
+
+    /// Returns a [`DependencyFacts`] projection of the Distribution's `run`
+    /// and `build` package dependencies and its Postgres version
+    /// requirement, suitable for a dependency resolver. Dependency
+    /// `variations` are not included.
+    pub fn dependency_facts(&self) -> DependencyFacts {
+        let deps = self.dependencies();
+        let mut facts: Vec<DependencyFact> = deps
+            .and_then(Dependencies::packages)
+            .map(Packages::phases)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(phase, _)| matches!(*phase, "run" | "build"))
+            .flat_map(|(phase, p)| {
+                p.relationships().into_iter().flat_map(move |(rel, pkgs)| {
+                    pkgs.iter().map(move |(purl, range)| DependencyFact {
+                        phase: phase.to_string(),
+                        relation: rel.to_string(),
+                        purl: purl.clone(),
+                        range: range.clone(),
+                    })
+                })
+            })
+            .collect();
+        facts.sort_by(|a, b| {
+            (&a.phase, &a.relation, &a.purl).cmp(&(&b.phase, &b.relation, &b.purl))
+        });
+
+        DependencyFacts {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            postgres: deps
+                .and_then(Dependencies::postgres)
+                .map(|pg| pg.version().to_string()),
+            facts,
+        }
+    }
+
+    /// Returns every version and version range in the Distribution, each
+    /// tagged with the context in which it appears:
+    ///
+    /// *   `version`: The Distribution version
+    /// *   `postgres`: The PostgreSQL version requirement of the
+    ///     Dependencies and of each of their variations
+    /// *   The name of a package phase, such as `run` or `build`: The
+    ///     version range of a package dependency in that phase, including
+    ///     phases in variations
+    ///
+    /// The Distribution version comes first, followed by the PostgreSQL
+    /// requirements and then the package version ranges, sorted by phase,
+    /// relationship, and purl.
+    pub fn all_versions(&self) -> Vec<(&'static str, String)> {
+        let mut versions = vec![("version", self.version.to_string())];
+        let Some(deps) = self.dependencies() else {
+            return versions;
+        };
+
+        // Collect the Postgres requirements, starting with the base.
+        let mut pending = vec![deps];
+        while let Some(deps) = pending.pop() {
+            if let Some(pg) = deps.postgres() {
+                versions.push(("postgres", pg.version().to_string()));
+            }
+            for variation in deps.variations().unwrap_or_default().iter().rev() {
+                pending.push(variation.dependencies());
+            }
+        }
+
+        // Collect the package ranges.
+        let mut ranges: Vec<(&'static str, &'static str, &str, String)> = deps
+            .all_phases()
+            .into_iter()
+            .flat_map(|(phase, p)| {
+                p.relationships().into_iter().flat_map(move |(rel, pkgs)| {
+                    pkgs.iter()
+                        .map(move |(purl, range)| (phase, rel, purl.as_str(), range.to_string()))
+                })
+            })
+            .collect();
+        ranges.sort();
+        versions.extend(
+            ranges
+                .into_iter()
+                .map(|(phase, _, _, range)| (phase, range)),
+        );
+        versions
+    }
+
+    /// Returns the item name, role, and path of every file referenced by
+    /// the Distribution contents. The roles are `control`, `sql`, and `doc`
+    /// for extensions; `lib` and `doc` for modules; and `bin`, `lib`, `doc`,
+    /// `man`, and `html` for apps. Sorted by item name, role, and path.
+    pub fn path_roles(&self) -> Vec<(String, &'static str, &RelativePath)> {
+        let contents = &self.contents;
+        let mut roles = Vec::new();
+        for (name, ext) in contents.extensions().into_iter().flatten() {
+            roles.push((name.clone(), "control", ext.control().as_relative_path()));
+            roles.push((name.clone(), "sql", ext.sql().as_relative_path()));
+            if let Some(doc) = ext.doc() {
+                roles.push((name.clone(), "doc", doc));
+            }
+        }
+        for (name, module) in contents.modules().into_iter().flatten() {
+            roles.push((name.clone(), "lib", module.lib().as_relative_path()));
+            if let Some(doc) = module.doc() {
+                roles.push((name.clone(), "doc", doc));
+            }
+        }
+        for (name, app) in contents.apps().into_iter().flatten() {
+            roles.push((name.clone(), "bin", app.bin().as_relative_path()));
+            for (role, path) in [
+                ("lib", app.lib()),
+                ("doc", app.doc()),
+                ("man", app.man()),
+                ("html", app.html()),
+            ] {
+                if let Some(path) = path {
+                    roles.push((name.clone(), role, path));
+                }
+            }
+        }
+        roles.sort();
+        roles
+    }
+
+    /// Returns the number of distinct package purls the Distribution
+    /// depends on across all phases, including those declared in
+    /// variations.
+    pub fn dependency_count(&self) -> usize {
+        let Some(deps) = self.dependencies() else {
+            return 0;
+        };
+        deps.all_phases()
+            .into_iter()
+            .flat_map(|(_, phase)| phase.relationships())
+            .flat_map(|(_, rels)| rels.keys())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Borrows the base PostgreSQL version requirement of the Distribution,
+    /// ignoring any requirements declared in variations. Returns `None` if
+    /// the Distribution declares no PostgreSQL dependency.
+    pub fn postgres_requirement(&self) -> Option<&str> {
+        self.dependencies()?.postgres().map(Postgres::version)
+    }
+
+    /// Returns true if any module in the Distribution must be preloaded by
+    /// the server, and therefore requires a Postgres restart to install.
+    pub fn requires_restart(&self) -> bool {
+        !self.server_preload_modules().is_empty()
+    }
+
+    /// Borrows the names of all modules in the Distribution that must be
+    /// preloaded by the server, sorted by name.
+    pub fn server_preload_modules(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .contents
+            .modules()
+            .into_iter()
+            .flatten()
+            .filter(|(_, module)| module.preload() == Some(&Preload::Server))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns the names of all trusted language extensions (TLEs) that
+    /// share a name with a module in the Distribution contents. TLEs must
+    /// be pure SQL, so a same-named module implies compiled code the TLE
+    /// cannot ship. Sorted by name.
+    pub fn tle_with_native_code(&self) -> Vec<String> {
+        let Some(modules) = self.contents.modules() else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = self
+            .contents
+            .extensions()
+            .into_iter()
+            .flatten()
+            .filter(|(name, ext)| ext.tle() && modules.contains_key(*name))
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Returns the kind and name of every extension, module, and app in the
+    /// Distribution contents that has no `doc` path. The kinds are
+    /// `extension`, `module`, and `app`. Sorted by kind and name.
+    pub fn undocumented_items(&self) -> Vec<(&'static str, String)> {
+        let contents = &self.contents;
+        let mut items: Vec<(&'static str, String)> = Vec::new();
+        for (name, ext) in contents.extensions().into_iter().flatten() {
+            if ext.doc().is_none() {
+                items.push(("extension", name.clone()));
+            }
+        }
+        for (name, module) in contents.modules().into_iter().flatten() {
+            if module.doc().is_none() {
+                items.push(("module", name.clone()));
+            }
+        }
+        for (name, app) in contents.apps().into_iter().flatten() {
+            if app.doc().is_none() {
+                items.push(("app", name.clone()));
+            }
+        }
+        items.sort();
+        items
+    }
+
+    /// Returns the name and `doc` path of every documented extension,
+    /// module, and app in the Distribution contents, grouped by kind as
+    /// `(extensions, modules, apps)`. Each group is sorted by name. Items
+    /// without a `doc` path are omitted; see
+    /// [`undocumented_items`](Self::undocumented_items).
+    pub fn docs_by_kind(&self) -> (DocPaths<'_>, DocPaths<'_>, DocPaths<'_>) {
+        let contents = &self.contents;
+        let exts = contents.extensions().into_iter().flatten();
+        let modules = contents.modules().into_iter().flatten();
+        let apps = contents.apps().into_iter().flatten();
+        (
+            exts.filter_map(|(name, ext)| ext.doc().map(|doc| (name.as_str(), doc)))
+                .collect(),
+            modules
+                .filter_map(|(name, module)| module.doc().map(|doc| (name.as_str(), doc)))
+                .collect(),
+            apps.filter_map(|(name, app)| app.doc().map(|doc| (name.as_str(), doc)))
+                .collect(),
+        )
+    }
+
+    /// Returns the hex-encoded SHA-256 digest of the substantive content of
+    /// the Distribution. The digest excludes the `producer`, the
+    /// `meta-spec` `url`, and all custom `x_` and `X_` properties, which
+    /// may change without changing the distribution itself, so two
+    /// Distributions that differ only in those fields share a fingerprint.
+    pub fn content_fingerprint(&self) -> String {
+        use sha2::{Digest, Sha256};
+        let mut meta = serde_json::to_value(self).expect("Distribution serializes to JSON");
+        if let Value::Object(obj) = &mut meta {
+            obj.remove("producer");
+            if let Some(Value::Object(spec)) = obj.get_mut("meta-spec") {
+                spec.remove("url");
+            }
+
+            // Contents keys are item names, which may look like custom
+            // properties, so strip only inside the items.
+            let contents = obj.remove("contents");
+            strip_custom_props(&mut meta);
+            if let Some(Value::Object(mut contents)) = contents {
+                contents.retain(|key, _| !is_custom_prop(key));
+                for items in contents.values_mut() {
+                    if let Value::Object(items) = items {
+                        items.values_mut().for_each(strip_custom_props);
+                    }
+                }
+                meta["contents"] = Value::Object(contents);
+            }
+        }
+        hex::encode(Sha256::digest(meta.to_string()))
+    }
+
+    /// Returns true if the Distribution contains one or more apps.
+    pub fn has_apps(&self) -> bool {
+        self.contents.apps().is_some_and(|apps| !apps.is_empty())
+    }
+
+    /// Borrows the `bin` file location of every app in the Distribution.
+    /// Returns an empty vector if the Distribution contains no apps.
+    pub fn binaries(&self) -> Vec<&RelativePath> {
+        match self.contents.apps() {
+            Some(apps) => apps
+                .values()
+                .map(|app| app.bin().as_relative_path())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Borrows the distinct languages declared by the apps in the
+    /// Distribution, sorted. Apps that declare no language are omitted; use
+    /// [`apps_without_lang`](Self::apps_without_lang) to count them.
+    pub fn app_languages(&self) -> Vec<&str> {
+        let mut langs: Vec<&str> = self
+            .contents
+            .apps()
+            .into_iter()
+            .flat_map(BTreeMap::values)
+            .filter_map(App::lang)
+            .collect();
+        langs.sort_unstable();
+        langs.dedup();
+        langs
+    }
+
+    /// Returns the number of apps in the Distribution that declare no
+    /// language.
+    pub fn apps_without_lang(&self) -> usize {
+        self.contents
+            .apps()
+            .into_iter()
+            .flat_map(BTreeMap::values)
+            .filter(|app| app.lang().is_none())
+            .count()
+    }
+
+    /// Returns keywords for full-text search of the Distribution, sorted
+    /// and deduplicated. The list includes the classifications tags and
+    /// categories, the names of the extensions, modules, and apps in the
+    /// contents, and the words of the abstract. Tokenizes the abstract by
+    /// splitting it on every character that is neither alphanumeric nor a
+    /// hyphen or underscore. All keywords are lowercased.
+    pub fn search_keywords(&self) -> Vec<String> {
+        let mut words: BTreeSet<String> = BTreeSet::new();
+        if let Some(c) = &self.classifications {
+            let terms = c.tags().into_iter().chain(c.categories()).flatten();
+            words.extend(terms.map(|t| t.to_lowercase()));
+        }
+        let contents = &self.contents;
+        let names = (contents.extensions().into_iter().flat_map(BTreeMap::keys))
+            .chain(contents.modules().into_iter().flat_map(BTreeMap::keys))
+            .chain(contents.apps().into_iter().flat_map(BTreeMap::keys));
+        words.extend(names.map(|n| n.to_lowercase()));
+        words.extend(
+            self.abs_tract
+                .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+                .filter(|w| !w.is_empty())
+                .map(str::to_lowercase),
+        );
+        words.into_iter().collect()
+    }
+
+    /// Checks that each of `patches` is a JSON object before merging them
+    /// via the [RFC 7396] merge pattern, as by
+    /// [`TryFrom<&[Value]>`](#impl-TryFrom%3C%26%5BValue%5D%3E-for-Distribution).
+    /// A patch that is not an object replaces the entire document, which is
+    /// rarely intended. Returns an [`Error::PatchNotObject`] error with the
+    /// index of the first patch that is not an object.
+    ///
+    /// [RFC 7396]: https://www.rfc-editor.org/rfc/rfc7396.html
+    pub fn validate_patches(patches: &[&Value]) -> Result<(), Error> {
+        match patches.iter().position(|p| !p.is_object()) {
+            Some(idx) => Err(Error::PatchNotObject(idx)),
+            None => Ok(()),
+        }
+    }
+
+    /// Overlays `other` onto the Distribution via the [RFC 7396] merge
+    /// pattern and returns the merged result. Properties present in `other`
+    /// replace those in the Distribution, while properties absent from
+    /// `other` persist. Returns an error if the merged metadata is invalid.
+    ///
+    /// [RFC 7396]: https://www.rfc-editor.org/rfc/rfc7396.html
+    pub fn overlay(&self, other: &Distribution) -> Result<Distribution, Error> {
+        let mut meta = serde_json::to_value(self)?;
+        json_patch::merge(&mut meta, &serde_json::to_value(other)?);
+        meta.try_into()
+    }
+
+    /// Returns true if the Distribution version or any package dependency
+    /// version carries SemVer build metadata, such as `1.2.3+meta`, which
+    /// the v2 spec forbids. Useful for Distributions converted from v1
+    /// metadata or deserialized without validation.
+    pub fn has_forbidden_version_metadata(&self) -> bool {
+        !self.version.build.is_empty()
+            || self
+                .dependencies()
+                .is_some_and(Dependencies::has_build_metadata)
+    }
+
+    /// Returns a description of each way in which the Distribution name,
+    /// though valid according to the schema, is problematic for the PGXN
+    /// registry:
+    ///
+    /// *   The name consists solely of digits, as in `123`, and so is easily
+    ///     mistaken for a version or ID.
+    /// *   The name differs from the name of one of its extensions only by
+    ///     case, as in `Pair` and `pair`, which registries and case
+    ///     insensitive file systems may treat as the same name.
+    /// *   The name starts or ends with a hyphen, which reads as a command
+    ///     line option or a truncated name.
+    ///
+    /// Returns an empty vector if the name has none of these issues.
+    pub fn name_issues(&self) -> Vec<String> {
+        let name = self.name.as_str();
+        let mut issues = Vec::new();
+        if name.chars().all(|c| c.is_ascii_digit()) {
+            issues.push(format!("name {name} is all digits"));
+        }
+        for ext in self
+            .contents
+            .extensions()
+            .into_iter()
+            .flat_map(BTreeMap::keys)
+        {
+            if ext != name && ext.eq_ignore_ascii_case(name) {
+                issues.push(format!(
+                    "name {name} differs from extension {ext} only by case"
+                ));
+            }
+        }
+        if name.starts_with('-') {
+            issues.push(format!("name {name} starts with a hyphen"));
+        }
+        if name.ends_with('-') {
+            issues.push(format!("name {name} ends with a hyphen"));
+        }
+        issues
+    }
+
+    /// Returns the package dependency purls that use the wrong type for a
+    /// Postgres core extension: `pkg:pgxn` purls for core extensions, such
+    /// as `pkg:pgxn/hstore`, and `pkg:postgres` purls for extensions that
+    /// are not core extensions. See [`is_core_extension`] for the list
+    /// of core extensions.
+    pub fn miscategorized_dependencies(&self) -> Vec<String> {
+        let Some(deps) = self.dependencies() else {
+            return Vec::new();
+        };
+        let mut purls: Vec<String> = deps
+            .all_phases()
+            .into_iter()
+            .flat_map(|(_, phase)| phase.relationships())
+            .flat_map(|(_, rels)| rels.keys())
+            .filter(|purl| {
+                let Some((kind, name)) = purl_type_and_name(purl) else {
+                    return false;
+                };
+                let core = v1::is_core_extension(&name.to_lowercase());
+                (kind == "pgxn" && core) || (kind == "postgres" && !core)
+            })
+            .cloned()
+            .collect();
+        purls.sort();
+        purls.dedup();
+        purls
+    }
+
+    /// Returns the `ignore` globs that look like absolute file system paths
+    /// rather than paths anchored to the root of the distribution, as when
+    /// an author writes `/Users/theory/secret` instead of `/secret`. A glob
+    /// looks like an absolute path if it:
+    ///
+    /// *   Starts with a Windows drive letter, as in `C:\secret` or
+    ///     `c:/secret`
+    /// *   Starts with `~/`, referring to a home directory
+    /// *   Has at least two path segments, the first of which is a common
+    ///     top-level system directory, such as `/home/theory` or
+    ///     `/Users/theory/secret`
+    ///
+    /// This is a heuristic, so a distribution that really does have, say, a
+    /// top-level `home` directory may be flagged as well.
+    pub fn suspicious_ignore_globs(&self) -> Vec<&str> {
+        self.ignore()
+            .unwrap_or_default()
+            .iter()
+            .map(String::as_str)
+            .filter(|glob| looks_absolute(glob))
+            .collect()
+    }
+
+    /// Compiles the `ignore` globs into an [`IgnoreSet`]. Returns `None` if
+    /// the Distribution has no `ignore` list and an error if any of the globs
+    /// fails to compile.
+    pub fn ignore_matcher(&self) -> Result<Option<IgnoreSet>, Error> {
+        self.ignore().map(IgnoreSet::new).transpose()
+    }
+
+    /// Compiles the `ignore` globs and returns the name and path of each
+    /// extension `sql` or `control` file, module `lib`, or app `bin` that
+    /// they would exclude from the distribution. Returns an error if any of
+    /// the globs fails to compile.
+    pub fn content_shadowed_by_ignore(&self) -> Result<Vec<(String, RelativePathBuf)>, Error> {
+        let Some(ignore) = self.ignore_matcher()? else {
+            return Ok(Vec::new());
+        };
+        let mut shadowed: Vec<(String, RelativePathBuf)> = self
+            .contents
+            .essential_paths()
+            .into_iter()
+            .filter(|(_, path)| ignore.matches(path))
+            .map(|(name, path)| (name.to_string(), path.to_relative_path_buf()))
+            .collect();
+        shadowed.sort();
+        Ok(shadowed)
+    }
+
+    /// Classifies every file referenced by the Distribution contents, as
+    /// listed by [`path_roles`](Self::path_roles), against the unpacked
+    /// distribution in the `root` directory. Paths excluded by the `ignore`
+    /// globs are reported as ignored, whether or not they exist under
+    /// `root`; paths that don't exist under `root` are reported as missing;
+    /// and the rest as shipping. Since `lib` paths omit the shared library
+    /// suffix, a `lib` exists if the path plus `.so`, `.dll`, or `.dylib`
+    /// names a file, or, for an app `lib`, if the path names a directory.
+    /// Returns an error if any of the `ignore` globs fails to compile.
+    pub fn shipping_report<P: AsRef<Path>>(&self, root: P) -> Result<ShippingReport, Error> {
+        let root = root.as_ref();
+        let ignore = self.ignore_matcher()?;
+        let exists = |name: &str, role: &str, path: &RelativePath| {
+            if role != "lib" {
+                return path.to_path(root).exists();
+            }
+            let app_lib = self
+                .contents
+                .apps()
+                .and_then(|apps| apps.get(name))
+                .and_then(App::lib);
+            (app_lib == Some(path) && path.to_path(root).is_dir())
+                || LIBRARY_SUFFIXES.iter().any(|suffix| {
+                    RelativePathBuf::from(format!("{path}{suffix}"))
+                        .to_path(root)
+                        .is_file()
+                })
+        };
+        let mut report = ShippingReport::default();
+        for (name, role, path) in self.path_roles() {
+            let item = (name, role, path.to_relative_path_buf());
+            if ignore.as_ref().is_some_and(|i| i.matches(path)) {
+                report.ignored.push(item);
+            } else if exists(&item.0, role, path) {
+                report.shipping.push(item);
+            } else {
+                report.missing.push(item);
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Suffixes of shared library files, which module `lib` paths omit.
+const LIBRARY_SUFFIXES: [&str; 3] = [".so", ".dll", ".dylib"];
+
+/// The item name, role, and path of a file referenced by the contents of a
+/// [`Distribution`], as listed in a [`ShippingReport`].
+pub type ContentPath = (String, &'static str, RelativePathBuf);
+
+/// Reports which files referenced by the contents of a [`Distribution`] ship
+/// in the distribution, which are excluded by its `ignore` globs, and which
+/// are missing. Returned by [`Distribution::shipping_report`].
+#[derive(Default, PartialEq, Debug)]
+pub struct ShippingReport {
+    shipping: Vec<ContentPath>,
+    ignored: Vec<ContentPath>,
+    missing: Vec<ContentPath>,
+}
+
+impl ShippingReport {
+    /// Borrows the files that exist and are not ignored, sorted by item
+    /// name, role, and path.
+    pub fn shipping(&self) -> &[ContentPath] {
+        self.shipping.as_slice()
+    }
+
+    /// Borrows the files excluded by the `ignore` globs, sorted by item name,
+    /// role, and path.
+    pub fn ignored(&self) -> &[ContentPath] {
+        self.ignored.as_slice()
+    }
+
+    /// Borrows the files that are not ignored but do not exist, sorted by
+    /// item name, role, and path.
+    pub fn missing(&self) -> &[ContentPath] {
+        self.missing.as_slice()
+    }
+
+    /// Returns true if no files are ignored or missing.
+    pub fn is_clean(&self) -> bool {
+        self.ignored.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// A minimal projection of a [`Distribution`], suitable for search indexes,
+/// returned by [`Distribution::summary`].
+#[derive(Serialize, PartialEq, Debug)]
+pub struct DistributionSummary {
+    name: String,
+    version: Version,
+    #[serde(rename = "abstract")]
+    abs_tract: String,
+    license: String,
+    maintainers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    categories: Option<Vec<String>>,
+}
+
+impl DistributionSummary {
+    /// Borrows the DistributionSummary name.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Borrows the DistributionSummary version.
+    pub fn version(&self) -> &Version {
+        self.version.borrow()
+    }
+
+    /// Borrows the DistributionSummary abstract.
+    pub fn abs_tract(&self) -> &str {
+        self.abs_tract.as_str()
+    }
+
+    /// Borrows the DistributionSummary license string.
+    pub fn license(&self) -> &str {
+        self.license.as_str()
+    }
+
+    /// Borrows the DistributionSummary maintainer names.
+    pub fn maintainers(&self) -> &[String] {
+        self.maintainers.borrow()
+    }
+
+    /// Borrows the DistributionSummary tags.
+    pub fn tags(&self) -> Option<&[String]> {
+        self.tags.as_deref()
+    }
+
+    /// Borrows the DistributionSummary categories.
+    pub fn categories(&self) -> Option<&[String]> {
+        self.categories.as_deref()
+    }
+}
+
+/// A dependency-only projection of a [`Distribution`], suitable for feeding
+/// a dependency resolver, returned by [`Distribution::dependency_facts`].
+#[derive(Serialize, PartialEq, Debug)]
+pub struct DependencyFacts {
+    name: String,
+    version: Version,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    postgres: Option<String>,
+    facts: Vec<DependencyFact>,
+}
+
+impl DependencyFacts {
+    /// Borrows the DependencyFacts distribution name.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Borrows the DependencyFacts distribution version.
+    pub fn version(&self) -> &Version {
+        self.version.borrow()
+    }
+
+    /// Borrows the DependencyFacts Postgres version range.
+    pub fn postgres(&self) -> Option<&str> {
+        self.postgres.as_deref()
+    }
+
+    /// Borrows the DependencyFacts list of package dependencies, sorted by
+    /// phase, relation, and purl.
+    pub fn facts(&self) -> &[DependencyFact] {
+        self.facts.borrow()
+    }
+}
+
+/// A single package dependency in [`DependencyFacts`].
+#[derive(Serialize, PartialEq, Debug)]
+pub struct DependencyFact {
+    phase: String,
+    relation: String,
+    purl: String,
+    range: VersionRange,
+}
+
+impl DependencyFact {
+    /// Borrows the DependencyFact phase, either `run` or `build`.
+    pub fn phase(&self) -> &str {
+        self.phase.as_str()
+    }
+
+    /// Borrows the DependencyFact relation, one of `requires`,
+    /// `recommends`, `suggests`, or `conflicts`.
+    pub fn relation(&self) -> &str {
+        self.relation.as_str()
+    }
+
+    /// Borrows the DependencyFact package purl.
+    pub fn purl(&self) -> &str {
+        self.purl.as_str()
+    }
+
+    /// Borrows the DependencyFact version range.
+    pub fn range(&self) -> &VersionRange {
+        self.range.borrow()
+    }
+}
+
+/// Compiled [`Distribution`] `ignore` globs, returned by
+/// [`Distribution::ignore_matcher`]. A glob starting with `/` is anchored to
+/// the root of the distribution, while a glob without a slash matches at any
+/// depth. A path is ignored if it or any of its parent directories matches.
+#[derive(Clone, Debug)]
+pub struct IgnoreSet {
+    globs: Vec<Glob<'static>>,
+}
+
+impl IgnoreSet {
+    /// Compiles `patterns` into an IgnoreSet.
+    fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self, Error> {
+        let mut globs = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            let pattern = pattern.as_ref().trim_end_matches('/');
+            let expr = match pattern.strip_prefix('/') {
+                Some(anchored) => anchored.to_string(),
+                None if pattern.contains('/') => pattern.to_string(),
+                None => format!("**/{pattern}"),
+            };
+            globs.push(Glob::new(&expr)?.into_owned());
+        }
+        Ok(Self { globs })
+    }
+
+    /// Returns true if `path` or any of its parent directories matches one
+    /// of the globs.
+    pub fn matches(&self, path: &RelativePath) -> bool {
+        let path = path.normalize();
+        let mut current = Some(path.as_relative_path());
+        while let Some(dir) = current.filter(|p| !p.as_str().is_empty()) {
+            if self.globs.iter().any(|g| g.is_match(dir.as_str())) {
+                return true;
+            }
+            current = dir.parent();
+        }
+        false
+    }
+}
+
+impl std::fmt::Display for Distribution {
+    /// fmt writes a one-line summary of the Distribution name, version,
+    /// abstract, and license to f. Use the Debug format for full detail.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} — {} ({})",
+            self.name, self.version, self.abs_tract, self.license
+        )
+    }
 }
 
 impl TryFrom<Value> for Distribution {
@@ -1014,7 +3339,7 @@ impl TryFrom<&[Value]> for Distribution {
         }
 
         // Find the version of the first doc.
-        let version = util::get_version(&meta[0]).ok_or(Error::UnknownSpec)?;
+        let version = util::get_version(&meta[0])?;
 
         // Convert the first doc to v2 if necessary.
         let mut v2 = match version {
@@ -1,4 +1,5 @@
 use super::*;
+use crate::tests::v2::minimal_v2;
 use serde_json::{json, Value};
 use std::{
     fs::{self, File},
@@ -110,6 +111,158 @@ fn test_bad_corpus() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "yaml")]
+fn test_from_yaml() -> Result<(), Error> {
+    let yaml = r#"
+name: pair
+abstract: A key/value pair data type
+version: 0.1.8
+maintainers:
+  - name: theory
+    email: theory@pgxn.org
+license: PostgreSQL
+contents:
+  extensions:
+    pair:
+      sql: sql/pair.sql
+      control: pair.control
+meta-spec:
+  version: 2.0.0
+"#;
+
+    let dist = Distribution::from_yaml(yaml)?;
+    assert_eq!("pair", dist.name());
+    assert_eq!("0.1.8", dist.version().to_string());
+    assert_eq!("PostgreSQL", dist.license());
+
+    // Make sure we get a YAML parse error.
+    match Distribution::from_yaml("name: [pair") {
+        Ok(_) => panic!("Should have failed on invalid YAML but did not"),
+        Err(e) => assert!(matches!(e, Error::Yaml { .. }), "{e}"),
+    }
+
+    // Make sure we get a validation error.
+    match Distribution::from_yaml("name: pair\nmeta-spec: {version: 2.0.0}\n") {
+        Ok(_) => panic!("Should have failed on invalid META but did not"),
+        Err(e) => assert!(
+            e.to_string().contains(" missing properties 'version'"),
+            "{e}"
+        ),
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn test_to_toml() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    let dist = Distribution::try_from(meta.clone())?;
+    let toml = dist.to_toml()?;
+    for want in [
+        "name = \"pair\"",
+        "version = \"0.1.8\"",
+        "license = \"PostgreSQL\"",
+        "[[maintainers]]",
+        "[contents.extensions.pair]",
+        "[meta-spec]",
+    ] {
+        assert!(toml.contains(want), "missing {want} in:\n{toml}");
+    }
+
+    // Should round-trip.
+    let val: Value = toml::from_str(&toml).unwrap();
+    assert_eq!(serde_json::to_value(&dist)?, val);
+
+    // Nulls cannot be represented.
+    meta["x_nothing"] = json!({"list": [1, null]});
+    let dist = Distribution::try_from(meta)?;
+    match dist.to_toml() {
+        Ok(_) => panic!("Should have failed on null custom property"),
+        Err(e) => {
+            assert!(matches!(e, Error::Toml { .. }));
+            assert_eq!(
+                "cannot represent null value at /x_nothing/list/1 in TOML",
+                e.to_string()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "archives")]
+fn test_from_tar_gz() -> Result<(), Error> {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "corpus",
+        "archives",
+        "pair-0.1.8.tar.gz",
+    ]
+    .iter()
+    .collect();
+    let dist = Distribution::from_tar_gz(path)?;
+    assert_eq!("pair", dist.name());
+    assert_eq!("0.1.8", dist.version().to_string());
+    assert_eq!("PostgreSQL", dist.license());
+
+    // Build archives to test the layout rules.
+    let meta = serde_json::to_vec(&minimal_v2()?)?;
+    let tmp = tempfile::tempdir()?;
+    for (name, files, err) in [
+        ("root", vec!["META.json"], None),
+        (
+            "nested ignored",
+            vec!["pair/META.json", "pair/t/META.json"],
+            None,
+        ),
+        (
+            "none",
+            vec!["pair/README.md"],
+            Some("no META.json file in archive"),
+        ),
+        (
+            "too deep",
+            vec!["pair/t/META.json"],
+            Some("no META.json file in archive"),
+        ),
+        (
+            "multiple",
+            vec!["pair/META.json", "widget/META.json"],
+            Some("multiple META.json files in archive"),
+        ),
+    ] {
+        let path = tmp.path().join(format!("{name}.tar.gz"));
+        let gz = flate2::write::GzEncoder::new(File::create(&path)?, Default::default());
+        let mut builder = tar::Builder::new(gz);
+        for file in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(meta.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, file, meta.as_slice())?;
+        }
+        builder.into_inner()?.finish()?;
+
+        match (Distribution::from_tar_gz(&path), err) {
+            (Ok(dist), None) => assert_eq!("pair", dist.name(), "{name}"),
+            (Ok(_), Some(err)) => panic!("{name} unexpectedly succeeded; expected {err}"),
+            (Err(e), None) => panic!("{name} failed: {e}"),
+            (Err(e), Some(err)) => assert_eq!(err, e.to_string(), "{name}"),
+        }
+    }
+
+    // Make sure we get an IO error for a missing file.
+    match Distribution::from_tar_gz(tmp.path().join("nonesuch.tar.gz")) {
+        Ok(_) => panic!("Should have failed on missing file but did not"),
+        Err(e) => assert!(matches!(e, Error::Io { .. }), "{e}"),
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_try_merge_v1() -> Result<(), Error> {
     // Load a v1 META file.
@@ -503,6 +656,85 @@ fn test_maintainer() {
     }
 }
 
+#[test]
+fn test_parse_maintainer_string() {
+    for (name, input, exp_name, exp_email) in [
+        (
+            "email only",
+            "theory@pgxn.org",
+            "theory@pgxn.org",
+            Some("theory@pgxn.org"),
+        ),
+        (
+            "display and email",
+            "David <theory@pgxn.org>",
+            "David",
+            Some("theory@pgxn.org"),
+        ),
+        ("name only", "David E. Wheeler", "David E. Wheeler", None),
+    ] {
+        let maintainer = parse_maintainer_string(input);
+        assert_eq!(exp_name, maintainer.name(), "{name} name");
+        assert_eq!(exp_email, maintainer.email(), "{name} email");
+        assert!(maintainer.url().is_none(), "{name} url");
+        assert!(maintainer.custom_props().is_empty(), "{name} custom_props");
+    }
+}
+
+#[test]
+fn test_is_valid_email() {
+    for (email, exp) in [
+        ("x@example.com", true),
+        ("theory+pgxn@pgxn.org", true),
+        ("not", false),
+        ("", false),
+        ("x@", false),
+        ("@example.com", false),
+        ("David <theory@pgxn.org>", false),
+        ("<theory@pgxn.org>", false),
+        (" x@example.com", false),
+    ] {
+        assert_eq!(exp, is_valid_email(email), "{email:?}");
+    }
+
+    // Agree with parse_maintainer_string for bare addresses.
+    assert_eq!(
+        Some("x@example.com"),
+        parse_maintainer_string("x@example.com").email()
+    );
+    assert_eq!(None, parse_maintainer_string("not").email());
+}
+
+#[test]
+fn test_id() -> Result<(), Error> {
+    let dist = Distribution::try_from(minimal_v2()?)?;
+    assert_eq!("pair@0.1.8", dist.id());
+    let (name, version) = parse_id(&dist.id())?;
+    assert_eq!(dist.name(), name);
+    assert_eq!(dist.version(), &version);
+
+    for (id, name, version) in [
+        ("pair@1.0.0-beta1", "pair", "1.0.0-beta1"),
+        ("a@b@2.0.0", "a@b", "2.0.0"),
+    ] {
+        let (n, v) = parse_id(id)?;
+        assert_eq!(name, n, "{id}");
+        assert_eq!(version, v.to_string(), "{id}");
+    }
+
+    for id in ["pair", "pair@", "@0.1.8", "pair@0.1", "pair@x.y.z", ""] {
+        match parse_id(id) {
+            Ok(res) => panic!("{id:?} unexpectedly parsed: {res:?}"),
+            Err(e) => assert_eq!(
+                format!("invalid v2 distribution id value: {:?}", id),
+                e.to_string(),
+            ),
+        }
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_extension() {
     for (name, json) in [
@@ -753,21 +985,21 @@ fn test_contents() {
         match json.get("extensions") {
             None => assert!(contents.extensions().is_none(), "{name} extensions"),
             Some(e) => {
-                let ext: HashMap<String, Extension> = serde_json::from_value(e.clone()).unwrap();
+                let ext: BTreeMap<String, Extension> = serde_json::from_value(e.clone()).unwrap();
                 assert_eq!(&ext, contents.extensions().unwrap(), "{name} extensions");
             }
         }
         match json.get("modules") {
             None => assert!(contents.modules().is_none(), "{name} modules"),
             Some(m) => {
-                let modules: HashMap<String, Module> = serde_json::from_value(m.clone()).unwrap();
+                let modules: BTreeMap<String, Module> = serde_json::from_value(m.clone()).unwrap();
                 assert_eq!(&modules, contents.modules().unwrap(), "{name} modules");
             }
         }
         match json.get("apps") {
             None => assert!(contents.apps().is_none(), "{name} apps"),
             Some(a) => {
-                let apps: HashMap<String, App> = serde_json::from_value(a.clone()).unwrap();
+                let apps: BTreeMap<String, App> = serde_json::from_value(a.clone()).unwrap();
                 assert_eq!(&apps, contents.apps().unwrap(), "{name} apps");
             }
         }
@@ -879,6 +1111,187 @@ fn test_version_range() {
     }
 }
 
+#[test]
+fn test_version_range_normalized() -> Result<(), Error> {
+    for (name, json, exp) in [
+        ("integer zero", json!(0), json!(0)),
+        ("string zero", json!("0"), json!(0)),
+        ("string", json!("1.2.0"), json!("1.2.0")),
+        (
+            "range",
+            json!(">= 1.2.0, < 2.0.0"),
+            json!(">= 1.2.0, < 2.0.0"),
+        ),
+    ] {
+        let range: VersionRange = serde_json::from_value(json)?;
+        let norm = range.normalized();
+        assert_eq!(
+            serde_json::from_value::<VersionRange>(exp.clone())?,
+            norm,
+            "{name}"
+        );
+        assert_eq!(exp, serde_json::to_value(&norm)?, "{name} serialized");
+    }
+
+    // Both forms of "any version" must normalize to the same value.
+    let int: VersionRange = serde_json::from_value(json!(0))?;
+    let str: VersionRange = serde_json::from_value(json!("0"))?;
+    assert_ne!(int, str);
+    assert_eq!(int.normalized(), str.normalized());
+    assert_eq!(
+        serde_json::to_string(&int.normalized())?,
+        serde_json::to_string(&str.normalized())?,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_version_range_overlaps() -> Result<(), Error> {
+    for (name, a, b, exp) in [
+        ("overlap", json!(">=1.0, <2.0"), json!(">=1.5"), true),
+        ("disjoint", json!("<1.0"), json!(">=2.0"), false),
+        ("integer any", json!(0), json!("<1.0"), true),
+        ("string any", json!(">=2.0"), json!("0"), true),
+        ("both any", json!(0), json!("0"), true),
+        ("bare minimum", json!("1.2"), json!("<1.3"), true),
+        ("bare minimum disjoint", json!("2"), json!("<1.9.9"), false),
+        ("touching inclusive", json!("<=2.0"), json!(">=2.0"), true),
+        ("touching exclusive", json!("<2.0"), json!(">=2.0"), false),
+        ("exact match", json!("==1.5.2"), json!(">=1.0, <2.0"), true),
+        (
+            "exact outside",
+            json!("==2.5.2"),
+            json!(">=1.0, <2.0"),
+            false,
+        ),
+        (
+            "excluded point",
+            json!("==1.5.2"),
+            json!(">=1.0, !=1.5.2"),
+            false,
+        ),
+        (
+            "excluded in range",
+            json!(">=1.0, <2.0"),
+            json!("!=1.5.2"),
+            true,
+        ),
+        ("unbounded above", json!(">1.0"), json!(">5.0"), true),
+        ("unbounded below", json!("<1.0"), json!("<0.5"), true),
+        ("pre-release", json!("<1.0.0"), json!(">=1.0.0-alpha"), true),
+    ] {
+        let a: VersionRange = serde_json::from_value(a)?;
+        let b: VersionRange = serde_json::from_value(b)?;
+        assert_eq!(exp, a.overlaps(&b)?, "{name}");
+        assert_eq!(exp, b.overlaps(&a)?, "{name} reversed");
+    }
+
+    // Make sure invalid ranges fail.
+    let valid = VersionRange::String("1.0".to_string());
+    for (name, range, err) in [
+        (
+            "bad string",
+            VersionRange::String("x.y.z".to_string()),
+            "invalid v2 version range value: \"x.y.z\"",
+        ),
+        (
+            "bad integer",
+            VersionRange::Integer(2),
+            "invalid v2 version range value: 2",
+        ),
+    ] {
+        match range.overlaps(&valid) {
+            Ok(_) => panic!("{name} unexpectedly succeeded"),
+            Err(e) => assert_eq!(err, e.to_string(), "{name}"),
+        }
+        match valid.overlaps(&range) {
+            Ok(_) => panic!("{name} reversed unexpectedly succeeded"),
+            Err(e) => assert_eq!(err, e.to_string(), "{name} reversed"),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_version_range_version_req() -> Result<(), Error> {
+    // Round-trip.
+    for (name, range, req) in [
+        ("any", json!(0), "*"),
+        ("bounded", json!(">=1.2.0, <2.0.0"), ">=1.2.0, <2.0.0"),
+        ("exact", json!("==1.2.3-beta1"), "=1.2.3-beta1"),
+        ("inclusive", json!(">1.0.0, <=1.5.0"), ">1.0.0, <=1.5.0"),
+    ] {
+        let range: VersionRange = serde_json::from_value(range)?;
+        let vr = VersionReq::try_from(&range)?;
+        assert_eq!(req, vr.to_string(), "{name}");
+        assert_eq!(range, VersionRange::from(&vr), "{name}");
+    }
+
+    // One-way conversions to VersionReq.
+    for (name, range, req) in [
+        ("string any", json!("0"), "*"),
+        ("bare minimum", json!("1.2"), ">=1.2"),
+        ("partial", json!(">= 2, < 3.1"), ">=2, <3.1"),
+        ("build metadata", json!("<= 1.1.2+meta"), "<=1.1.2"),
+    ] {
+        let range: VersionRange = serde_json::from_value(range)?;
+        assert_eq!(req, VersionReq::try_from(&range)?.to_string(), "{name}");
+    }
+
+    // Ranges VersionReq cannot represent.
+    for (name, range) in [
+        ("not equal", json!(">= 1.0.0, != 1.2.0")),
+        ("partial pre-release", json!("< 2-beta")),
+        ("unparseable", json!("1.x")),
+        ("bad integer", json!(1)),
+    ] {
+        let range: VersionRange = serde_json::from_value(range)?;
+        match VersionReq::try_from(&range) {
+            Ok(vr) => panic!("{name} unexpectedly converted to {vr}"),
+            Err(e) => assert!(
+                matches!(e, Error::Invalid("version range", 2, _)),
+                "{name}: {e}"
+            ),
+        }
+    }
+
+    // One-way conversions from VersionReq.
+    for (name, req, range) in [
+        ("caret", "^1.2", ">=1.2.0, <2.0.0"),
+        ("caret zero", "^0.2.3", ">=0.2.3, <0.3.0"),
+        ("caret zero zero", "^0.0.3", ">=0.0.3, <0.0.4"),
+        ("tilde", "~1.2.3", ">=1.2.3, <1.3.0"),
+        ("tilde major", "~1", ">=1.0.0, <2.0.0"),
+        ("wildcard", "1.2.*", ">=1.2.0, <1.3.0"),
+        ("partial exact", "=1", ">=1.0.0, <2.0.0"),
+        ("partial greater", ">1.2", ">=1.3.0"),
+        ("partial less equal", "<=1", "<2.0.0"),
+        ("partial less", "<1.2", "<1.2.0"),
+        ("multiple", ">=1.0, <1.5", ">=1.0.0, <1.5.0"),
+    ] {
+        let vr = VersionReq::parse(req).unwrap();
+        assert_eq!(
+            VersionRange::String(range.to_string()),
+            VersionRange::from(&vr),
+            "{name}"
+        );
+    }
+
+    // Conversion agrees with Postgres::parsed_version on partial versions.
+    for range in ["<= 12", "> 12", "== 16", "14, < 18.1"] {
+        let pg: Postgres = serde_json::from_value(json!({"version": range}))?;
+        let vr = VersionReq::try_from(&VersionRange::String(range.to_string()))?;
+        assert_eq!(pg.parsed_version()?, vr, "{range}");
+    }
+    let vr = VersionReq::try_from(&VersionRange::String("<= 12".to_string()))?;
+    assert!(vr.matches(&Version::new(12, 1, 0)));
+    assert!(!vr.matches(&Version::new(13, 0, 0)));
+
+    Ok(())
+}
+
 #[test]
 fn test_phase() {
     for (name, json) in [
@@ -904,28 +1317,28 @@ fn test_phase() {
         match json.get("requires") {
             None => assert!(phase.requires().is_none(), "{name} requires"),
             Some(p) => {
-                let p: HashMap<String, VersionRange> = serde_json::from_value(p.clone()).unwrap();
+                let p: BTreeMap<String, VersionRange> = serde_json::from_value(p.clone()).unwrap();
                 assert_eq!(&p, phase.requires().unwrap(), "{name} requires");
             }
         }
         match json.get("recommends") {
             None => assert!(phase.recommends().is_none(), "{name} recommends"),
             Some(p) => {
-                let p: HashMap<String, VersionRange> = serde_json::from_value(p.clone()).unwrap();
+                let p: BTreeMap<String, VersionRange> = serde_json::from_value(p.clone()).unwrap();
                 assert_eq!(&p, phase.recommends().unwrap(), "{name} recommends");
             }
         }
         match json.get("suggests") {
             None => assert!(phase.suggests().is_none(), "{name} suggests"),
             Some(p) => {
-                let p: HashMap<String, VersionRange> = serde_json::from_value(p.clone()).unwrap();
+                let p: BTreeMap<String, VersionRange> = serde_json::from_value(p.clone()).unwrap();
                 assert_eq!(&p, phase.suggests().unwrap(), "{name} suggests");
             }
         }
         match json.get("conflicts") {
             None => assert!(phase.conflicts().is_none(), "{name} conflicts"),
             Some(p) => {
-                let p: HashMap<String, VersionRange> = serde_json::from_value(p.clone()).unwrap();
+                let p: BTreeMap<String, VersionRange> = serde_json::from_value(p.clone()).unwrap();
                 assert_eq!(&p, phase.conflicts().unwrap(), "{name} conflicts");
             }
         }
@@ -1156,6 +1569,103 @@ fn test_dependencies() {
     }
 }
 
+#[test]
+fn test_unreachable_variations() {
+    let var = |platforms: Value| {
+        json!({
+            "where": {"platforms": platforms},
+            "dependencies": {"packages": {"build": {"requires": {"pkg:generic/clang": 0}}}},
+        })
+    };
+    for (name, json, exp) in [
+        (
+            "no variations",
+            json!({"platforms": ["linux", "darwin"]}),
+            vec![],
+        ),
+        (
+            "no base platforms",
+            json!({"variations": [var(json!(["windows"]))]}),
+            vec![],
+        ),
+        (
+            "unreachable",
+            json!({"platforms": ["linux", "darwin"], "variations": [var(json!(["windows"]))]}),
+            vec![0],
+        ),
+        (
+            "intersecting",
+            json!({"platforms": ["linux", "darwin"], "variations": [var(json!(["windows", "darwin"]))]}),
+            vec![],
+        ),
+        (
+            "os match",
+            json!({"platforms": ["linux"], "variations": [var(json!(["linux-arm64"]))]}),
+            vec![],
+        ),
+        (
+            "any",
+            json!({"platforms": ["any"], "variations": [var(json!(["windows"]))]}),
+            vec![],
+        ),
+        (
+            "no where platforms",
+            json!({"platforms": ["linux"], "variations": [{
+                "where": {"postgres": {"version": ">= 16.0"}},
+                "dependencies": {"postgres": {"version": ">= 16.0", "with": ["zstd"]}},
+            }]}),
+            vec![],
+        ),
+        (
+            "mixed",
+            json!({"platforms": ["linux", "darwin"], "variations": [
+                var(json!(["darwin"])),
+                var(json!(["windows"])),
+                var(json!(["linux-amd64"])),
+                var(json!(["freebsd", "openbsd"])),
+            ]}),
+            vec![1, 3],
+        ),
+    ] {
+        let deps: Dependencies = serde_json::from_value(json).unwrap();
+        assert_eq!(exp, deps.unreachable_variations(), "{name}");
+    }
+}
+
+#[test]
+fn test_dependency_sources() {
+    for (name, json, exp) in [
+        (
+            "no packages",
+            json!({"postgres": {"version": "14"}}),
+            vec![],
+        ),
+        (
+            "mixed",
+            json!({
+                "packages": {
+                    "build": {"requires": {"pkg:generic/python": "3.0", "pkg:pgxn/pgtap": 0}},
+                    "run": {
+                        "requires": {"pkg:postgres/plpgsql": 0, "pkg:pgxn/semver": "0.32"},
+                        "recommends": {"pkg:generic/jq": 0},
+                    },
+                },
+                "variations": [{
+                    "where": {"platforms": ["linux"]},
+                    "dependencies": {"packages": {"run": {"requires": {
+                        "pkg:pypi/psycopg": "3.2",
+                        "pkg:generic/python": "3.10",
+                    }}}},
+                }],
+            }),
+            vec!["generic", "pgxn", "postgres", "pypi"],
+        ),
+    ] {
+        let deps: Dependencies = serde_json::from_value(json).unwrap();
+        assert_eq!(exp, deps.dependency_sources(), "{name}");
+    }
+}
+
 #[test]
 fn test_badge() {
     for (name, json) in [
@@ -1451,10 +1961,2484 @@ fn test_distribution() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_distribution_apps() -> Result<(), Error> {
+    let mut meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "extensions": {
+          "pair": { "sql": "sql/pair.sql", "control": "pair.control" }
+        }
+      },
+      "meta-spec": { "version": "2.0.0" }
+    });
+
+    // No apps.
+    let dist = Distribution::try_from(meta.clone())?;
+    assert!(!dist.has_apps());
+    assert!(dist.binaries().is_empty());
+
+    // Two apps.
+    json_patch::merge(
+        &mut meta,
+        &json!({"contents": {"apps": {
+          "pair_dump": { "bin": "bin/pair_dump" },
+          "pair_load": { "bin": "bin/pair_load", "lang": "perl" },
+        }}}),
+    );
+    let dist = Distribution::try_from(meta)?;
+    assert!(dist.has_apps());
+    let mut bins = dist.binaries();
+    bins.sort();
+    assert_eq!(
+        vec![
+            RelativePath::new("bin/pair_dump"),
+            RelativePath::new("bin/pair_load")
+        ],
+        bins,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_app_languages() -> Result<(), Error> {
+    let meta = minimal_v2()?;
+
+    for (name, apps, langs, no_lang) in [
+        ("no apps", json!(null), vec![], 0),
+        (
+            "no lang",
+            json!({"pair_dump": {"bin": "bin/pair_dump"}}),
+            vec![],
+            1,
+        ),
+        (
+            "mixed",
+            json!({
+                "pair_dump": {"bin": "bin/pair_dump", "lang": "sh"},
+                "pair_load": {"bin": "bin/pair_load", "lang": "perl"},
+                "pair_sync": {"bin": "bin/pair_sync", "lang": "perl"},
+                "pair_tool": {"bin": "bin/pair_tool"},
+            }),
+            vec!["perl", "sh"],
+            1,
+        ),
+    ] {
+        let mut meta = meta.clone();
+        json_patch::merge(&mut meta, &json!({"contents": {"apps": apps}}));
+        let dist = Distribution::try_from(meta)?;
+        assert_eq!(langs, dist.app_languages(), "{name} languages");
+        assert_eq!(no_lang, dist.apps_without_lang(), "{name} no lang");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_converted_from_v1() -> Result<(), Error> {
+    let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus"].iter().collect();
+    let v1_meta: Value = serde_json::from_reader(File::open(dir.join("v1").join("widget.json"))?)?;
+    let v2_meta = minimal_v2()?;
+
+    // Marked conversion.
+    let marked = v1::to_v2_marked(&v1_meta)?;
+    assert_eq!(Some(&json!(true)), marked.get(v1::FROM_V1_PROPERTY));
+    let dist = Distribution::try_from(marked)?;
+    assert!(dist.converted_from_v1());
+
+    // The marker survives serialization.
+    let val: Value = dist.try_into()?;
+    assert!(Distribution::try_from(val)?.converted_from_v1());
+
+    // Unmarked conversion.
+    let dist = Distribution::try_from(v1::to_v2(&v1_meta)?)?;
+    assert!(!dist.converted_from_v1());
+
+    // Native v2.
+    let dist = Distribution::try_from(v2_meta)?;
+    assert!(!dist.converted_from_v1());
+
+    Ok(())
+}
+
+#[test]
+fn test_content_shadowed_by_ignore() -> Result<(), Error> {
+    let meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "extensions": {
+          "pair": { "sql": "sql/pair.sql", "control": "pair.control" }
+        },
+        "modules": {
+          "pair": { "type": "extension", "lib": "src/pair" }
+        }
+      },
+      "meta-spec": { "version": "2.0.0" }
+    });
+
+    for (name, ignore, exp) in [
+        ("none", None, vec![]),
+        (
+            "benign",
+            Some(json!(["/.git", "*.tmp", "doc/*.html"])),
+            vec![],
+        ),
+        (
+            "sql files",
+            Some(json!(["*.sql"])),
+            vec![("pair", "sql/pair.sql")],
+        ),
+        (
+            "anchored dir",
+            Some(json!(["/src"])),
+            vec![("pair", "src/pair")],
+        ),
+        (
+            "multiple",
+            Some(json!(["/sql/", "*.control"])),
+            vec![("pair", "pair.control"), ("pair", "sql/pair.sql")],
+        ),
+    ] {
+        let mut meta = meta.clone();
+        if let Some(ignore) = ignore {
+            meta.as_object_mut()
+                .unwrap()
+                .insert("ignore".to_string(), ignore);
+        }
+        let dist = Distribution::try_from(meta)?;
+        let exp: Vec<(String, RelativePathBuf)> = exp
+            .into_iter()
+            .map(|(n, p)| (n.to_string(), RelativePathBuf::from(p)))
+            .collect();
+        assert_eq!(exp, dist.content_shadowed_by_ignore()?, "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_shipping_report() -> Result<(), Error> {
+    let meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "extensions": {
+          "pair": { "sql": "sql/pair.sql", "control": "pair.control", "doc": "doc/pair.md" }
+        },
+        "modules": {
+          "pair": { "type": "extension", "lib": "src/pair" }
+        },
+        "apps": {
+          "pair_cli": { "bin": "bin/pair_cli", "lib": "lib", "man": "man/pair_cli.1" }
+        }
+      },
+      "ignore": ["/src", "*.1"],
+      "meta-spec": { "version": "2.0.0" }
+    });
+    let dist = Distribution::try_from(meta.clone())?;
+
+    let tmp = tempfile::tempdir()?;
+    for file in [
+        "sql/pair.sql",
+        "pair.control",
+        "src/pair.so",
+        "bin/pair_cli",
+    ] {
+        let path = RelativePath::new(file).to_path(tmp.path());
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        File::create(path)?;
+    }
+    std::fs::create_dir(tmp.path().join("lib"))?;
+
+    let items = |list: &[(&str, &'static str, &str)]| -> Vec<ContentPath> {
+        list.iter()
+            .map(|(n, r, p)| (n.to_string(), *r, RelativePathBuf::from(p)))
+            .collect()
+    };
+
+    let report = dist.shipping_report(tmp.path())?;
+    assert_eq!(
+        items(&[
+            ("pair", "control", "pair.control"),
+            ("pair", "sql", "sql/pair.sql"),
+            ("pair_cli", "bin", "bin/pair_cli"),
+            ("pair_cli", "lib", "lib"),
+        ]),
+        report.shipping(),
+    );
+    assert_eq!(
+        items(&[
+            ("pair", "lib", "src/pair"),
+            ("pair_cli", "man", "man/pair_cli.1")
+        ]),
+        report.ignored(),
+    );
+    assert_eq!(items(&[("pair", "doc", "doc/pair.md")]), report.missing());
+    assert!(!report.is_clean());
+
+    // Without ignore globs, the ignored files that exist ship.
+    let mut meta = meta;
+    meta.as_object_mut().unwrap().remove("ignore");
+    meta["contents"]["extensions"]["pair"]
+        .as_object_mut()
+        .unwrap()
+        .remove("doc");
+    meta["contents"]["apps"]["pair_cli"]
+        .as_object_mut()
+        .unwrap()
+        .remove("man");
+    let dist = Distribution::try_from(meta)?;
+    let report = dist.shipping_report(tmp.path())?;
+    assert_eq!(5, report.shipping().len());
+    assert!(report.shipping().contains(&(
+        "pair".to_string(),
+        "lib",
+        RelativePathBuf::from("src/pair")
+    )));
+    assert!(report.ignored().is_empty());
+    assert!(report.missing().is_empty());
+    assert!(report.is_clean());
+
+    // A module lib needs a shared library suffix.
+    std::fs::rename(tmp.path().join("src/pair.so"), tmp.path().join("src/pair"))?;
+    let report = dist.shipping_report(tmp.path())?;
+    assert_eq!(items(&[("pair", "lib", "src/pair")]), report.missing());
+
+    Ok(())
+}
+
+#[test]
+fn test_artifact_validate_platform() -> Result<(), Error> {
+    let url = "https://github.com/theory/pg-pair/releases/download/v1.1.0/pair-1.1.0.zip";
+
+    // Test valid platforms.
+    for (name, platform) in [
+        ("none", None),
+        ("linux-amd64", Some("linux-amd64")),
+        ("darwin", Some("darwin")),
+        ("musllinux-1.2-arm64", Some("musllinux-1.2-arm64")),
+    ] {
+        let mut json = json!({"type": "bin", "url": url});
+        if let Some(p) = platform {
+            json["platform"] = json!(p);
+        }
+        let art: Artifact = serde_json::from_value(json)?;
+        if let Err(e) = art.validate_platform() {
+            panic!("{name} failed: {e}");
+        }
+    }
+
+    // Test invalid platforms.
+    for (name, platform) in [
+        ("x86_64", "x86_64"),
+        ("empty", ""),
+        ("space", "linux amd64"),
+    ] {
+        let art: Artifact =
+            serde_json::from_value(json!({"type": "bin", "url": url, "platform": platform}))?;
+        if art.validate_platform().is_ok() {
+            panic!("{name} unexpectedly passed!");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_from_value_with_version() -> Result<(), Error> {
+    let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus"].iter().collect();
+    for (version, file) in [(1, "v1/widget.json"), (2, "v2/typical-sql.json")] {
+        let meta: Value = serde_json::from_reader(File::open(dir.join(file))?)?;
+        let exp = Distribution::try_from(meta.clone())?;
+        let (v, dist) = Distribution::from_value_with_version(meta)?;
+        assert_eq!(version, v, "{file}");
+        assert_eq!(exp, dist, "{file}");
+    }
+
+    // Make sure invalid metadata fails.
+    match Distribution::from_value_with_version(json!({"meta-spec": {"version": "2.0.0"}})) {
+        Ok(_) => panic!("invalid metadata unexpectedly succeeded"),
+        Err(e) => assert!(matches!(e, Error::ValidationError { .. }), "{e}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_overlay() -> Result<(), Error> {
+    let meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "extensions": {
+          "pair": { "sql": "sql/pair.sql", "control": "pair.control" }
+        }
+      },
+      "resources": { "homepage": "https://pgxn.org/dist/pair/" },
+      "meta-spec": { "version": "2.0.0" }
+    });
+    let dist = Distribution::try_from(meta.clone())?;
+
+    // Overlay a distribution that changes the license and lacks resources.
+    let mut patch = meta.clone();
+    json_patch::merge(&mut patch, &json!({"license": "MIT", "resources": null}));
+    let other = Distribution::try_from(patch)?;
+
+    let merged = dist.overlay(&other)?;
+    assert_eq!("MIT", merged.license());
+    assert_eq!(dist.name(), merged.name());
+    assert_eq!(dist.version(), merged.version());
+    assert_eq!(dist.contents(), merged.contents());
+    assert_eq!(dist.resources(), merged.resources());
+
+    // The original is unchanged.
+    assert_eq!("PostgreSQL", dist.license());
+
+    Ok(())
+}
+
+#[test]
+fn test_has_forbidden_version_metadata() -> Result<(), Error> {
+    let meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "1.2.3",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "extensions": {
+          "pair": { "sql": "sql/pair.sql", "control": "pair.control" }
+        }
+      },
+      "meta-spec": { "version": "2.0.0" }
+    });
+
+    for (name, patch, exp) in [
+        ("plain", json!({}), false),
+        ("build metadata", json!({"version": "1.2.3+meta"}), true),
+        ("prerelease", json!({"version": "1.2.3-beta1"}), false),
+        (
+            "dependency metadata",
+            json!({"dependencies": {"packages": {"run": {"requires": {
+                "pkg:pgxn/citext": ">= 1.0.0+build, < 2.0.0",
+            }}}}}),
+            true,
+        ),
+        (
+            "dependency ranges",
+            json!({"dependencies": {"packages": {"run": {"requires": {
+                "pkg:pgxn/citext": ">= 1.0.0-beta1, < 2.0.0",
+                "pkg:pgxn/pair": 0,
+            }}}}}),
+            false,
+        ),
+        (
+            "variation metadata",
+            json!({"dependencies": {"variations": [{
+                "where": {"platforms": ["linux"]},
+                "dependencies": {"packages": {"build": {"requires": {
+                    "pkg:generic/python": "== 3.12.0+local",
+                }}}},
+            }]}}),
+            true,
+        ),
+    ] {
+        let mut meta = meta.clone();
+        json_patch::merge(&mut meta, &patch);
+        // Deserialize without validation, since v2 forbids build metadata.
+        let dist: Distribution = serde_json::from_value(meta)?;
+        assert_eq!(exp, dist.has_forbidden_version_metadata(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_contents_item() -> Result<(), Error> {
+    let meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "extensions": {
+          "pair": { "sql": "sql/pair.sql", "control": "pair.control" },
+          "trio": { "control": "trio.control" }
+        }
+      },
+      "meta-spec": { "version": "2.0.0" }
+    });
+
+    // The error should name the malformed extension.
+    match v2::from_value(meta.clone()) {
+        Ok(_) => panic!("malformed extension unexpectedly succeeded"),
+        Err(e) => assert_eq!("invalid v2 extension value: \"trio\"", e.to_string()),
+    }
+
+    // Modules and apps, too.
+    for (name, contents, err) in [
+        (
+            "module",
+            json!({"modules": {"pair": {"type": "hook"}}}),
+            "invalid v2 module value: \"pair\"",
+        ),
+        (
+            "app",
+            json!({"apps": {"pair_dump": {"lang": "perl"}}}),
+            "invalid v2 app value: \"pair_dump\"",
+        ),
+    ] {
+        let mut meta = meta.clone();
+        meta["contents"] = contents;
+        match v2::from_value(meta) {
+            Ok(_) => panic!("malformed {name} unexpectedly succeeded"),
+            Err(e) => assert_eq!(err, e.to_string(), "{name}"),
+        }
+    }
+
+    // Other errors should be unchanged.
+    let mut meta = meta.clone();
+    meta["contents"]["extensions"]["trio"]["sql"] = json!("sql/trio.sql");
+    meta.as_object_mut().unwrap().remove("license");
+    match v2::from_value(meta) {
+        Ok(_) => panic!("missing license unexpectedly succeeded"),
+        Err(e) => assert_eq!("missing field `license`", e.to_string()),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_primary_extension() -> Result<(), Error> {
+    let meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {},
+      "meta-spec": { "version": "2.0.0" }
+    });
+
+    for (name, contents, exp) in [
+        (
+            "name match",
+            json!({"extensions": {
+              "pair": { "sql": "sql/pair.sql", "control": "pair.control" },
+              "trio": { "sql": "sql/trio.sql", "control": "trio.control" },
+            }}),
+            Some(("pair", "sql/pair.sql")),
+        ),
+        (
+            "single extension",
+            json!({"extensions": {
+              "duo": { "sql": "sql/duo.sql", "control": "duo.control" },
+            }}),
+            Some(("duo", "sql/duo.sql")),
+        ),
+        (
+            "multiple without match",
+            json!({"extensions": {
+              "duo": { "sql": "sql/duo.sql", "control": "duo.control" },
+              "trio": { "sql": "sql/trio.sql", "control": "trio.control" },
+            }}),
+            None,
+        ),
+        (
+            "no extensions",
+            json!({"apps": { "pair_dump": { "bin": "bin/pair_dump" } }}),
+            None,
+        ),
+    ] {
+        let mut meta = meta.clone();
+        meta["contents"] = contents;
+        let dist = Distribution::try_from(meta)?;
+        let got = dist
+            .primary_extension()
+            .map(|(n, ext)| (n, ext.sql().as_str()));
+        assert_eq!(exp, got, "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_messy_tags() -> Result<(), Error> {
+    for (name, json, tags, categories) in [
+        ("empty", json!({}), vec![], vec![]),
+        (
+            "clean",
+            json!({"tags": ["unit testing", "xunit"], "categories": ["Data Types"]}),
+            vec![],
+            vec![],
+        ),
+        (
+            "messy tags",
+            json!({"tags": [" spaced ", "double  space", "clean", "trailing\t"]}),
+            vec![" spaced ", "double  space", "trailing\t"],
+            vec![],
+        ),
+        (
+            "messy categories",
+            json!({"categories": ["Data  Types", "Analytics "]}),
+            vec![],
+            vec!["Data  Types", "Analytics "],
+        ),
+    ] {
+        let class: Classifications = serde_json::from_value(json)?;
+        assert_eq!(tags, class.messy_tags(), "{name} tags");
+        assert_eq!(categories, class.messy_categories(), "{name} categories");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_summary() -> Result<(), Error> {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "corpus",
+        "v2",
+        "pg_partman.json",
+    ]
+    .iter()
+    .collect();
+    let dist = Distribution::load(path)?;
+    let summary = dist.summary();
+
+    assert_eq!("pg_partman", summary.name());
+    assert_eq!(dist.version(), summary.version());
+    assert_eq!(dist.abs_tract(), summary.abs_tract());
+    assert_eq!("PostgreSQL", summary.license());
+    assert_eq!(["Keith Fiske"], summary.maintainers());
+    assert_eq!(dist.classifications().unwrap().tags(), summary.tags());
+    assert_eq!(
+        Some(&["Orchestration".to_string()][..]),
+        summary.categories()
+    );
+
+    // Serialization includes only the summary fields.
+    let val = serde_json::to_value(&summary)?;
+    assert_eq!(
+        json!({
+          "name": "pg_partman",
+          "version": dist.version().to_string(),
+          "abstract": dist.abs_tract(),
+          "license": "PostgreSQL",
+          "maintainers": ["Keith Fiske"],
+          "tags": dist.classifications().unwrap().tags(),
+          "categories": ["Orchestration"],
+        }),
+        val,
+    );
+    assert!(val.get("contents").is_none());
+
+    // Tags and categories are omitted when absent.
+    let val = serde_json::to_value(Distribution::try_from(minimal_v2()?)?.summary())?;
+    assert!(val.get("tags").is_none());
+    assert!(val.get("categories").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_nonsql_extension_sql() -> Result<(), Error> {
+    for (name, json, exp) in [
+        ("no extensions", json!({}), vec![]),
+        (
+            "clean",
+            json!({"extensions": {
+              "pair": { "sql": "sql/pair.sql", "control": "pair.control" },
+              "trio": { "sql": "sql/TRIO.SQL", "control": "trio.control" },
+            }}),
+            vec![],
+        ),
+        (
+            "flagged",
+            json!({"extensions": {
+              "pair": { "sql": "sql/pair.txt", "control": "pair.control" },
+              "trio": { "sql": "sql/trio.sql", "control": "trio.control" },
+              "duo": { "sql": "sql/duo", "control": "duo.control" },
+            }}),
+            vec![("duo", "sql/duo"), ("pair", "sql/pair.txt")],
+        ),
+    ] {
+        let contents: Contents = serde_json::from_value(json)?;
+        let exp: Vec<(String, RelativePathBuf)> = exp
+            .into_iter()
+            .map(|(n, p)| (n.to_string(), RelativePathBuf::from(p)))
+            .collect();
+        assert_eq!(exp, contents.nonsql_extension_sql(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_extension_path_collisions() -> Result<(), Error> {
+    for (name, json, exp) in [
+        ("no extensions", json!({}), vec![]),
+        (
+            "clean",
+            json!({"extensions": {
+              "pair": { "sql": "sql/pair.sql", "control": "pair.control" },
+            }}),
+            vec![],
+        ),
+        (
+            "flagged",
+            json!({"extensions": {
+              "pair": { "sql": "pair.sql", "control": "pair.sql" },
+              "trio": { "sql": "sql/trio.sql", "control": "trio.control" },
+              "duo": { "sql": "./sql/duo.sql", "control": "sql/../sql/duo.sql" },
+            }}),
+            vec!["duo", "pair"],
+        ),
+    ] {
+        let contents: Contents = serde_json::from_value(json)?;
+        assert_eq!(exp, contents.extension_path_collisions(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_contents_get() -> Result<(), Error> {
+    let contents: Contents = serde_json::from_value(json!({
+        "extensions": {
+            "pair": { "sql": "sql/pair.sql", "control": "pair.control" },
+            "trio": { "sql": "sql/trio.sql", "control": "trio.control" },
+        },
+        "modules": {
+            "pair_hook": { "type": "hook", "lib": "lib/pair_hook" },
+            "trio": { "type": "extension", "lib": "lib/trio" },
+            "duo": { "type": "bgw", "lib": "lib/duo" },
+        },
+        "apps": {
+            "pair_dump": { "bin": "bin/pair_dump" },
+            "duo": { "bin": "bin/duo" },
+        },
+    }))?;
+    let exts = contents.extensions().unwrap();
+    let mods = contents.modules().unwrap();
+    let apps = contents.apps().unwrap();
+
+    for (name, exp) in [
+        ("pair", Some(ContentItem::Extension(&exts["pair"]))),
+        ("pair_hook", Some(ContentItem::Module(&mods["pair_hook"]))),
+        ("pair_dump", Some(ContentItem::App(&apps["pair_dump"]))),
+        ("trio", Some(ContentItem::Extension(&exts["trio"]))),
+        ("duo", Some(ContentItem::Module(&mods["duo"]))),
+        ("nonesuch", None),
+    ] {
+        assert_eq!(exp, contents.get(name), "{name}");
+    }
+
+    // Test empty contents.
+    let contents: Contents = serde_json::from_value(json!({}))?;
+    assert_eq!(None, contents.get("pair"));
+
+    Ok(())
+}
+
+#[test]
+fn test_miscategorized_dependencies() -> Result<(), Error> {
+    let meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "extensions": {
+          "pair": { "sql": "sql/pair.sql", "control": "pair.control" }
+        }
+      },
+      "meta-spec": { "version": "2.0.0" }
+    });
+
+    for (name, deps, exp) in [
+        ("none", None, vec![]),
+        (
+            "clean",
+            Some(json!({"packages": {"run": {"requires": {
+                "pkg:postgres/plpgsql": 0,
+                "pkg:pgxn/semver": "1.0.0",
+                "pkg:generic/python": 0,
+            }}}})),
+            vec![],
+        ),
+        (
+            "pgxn core",
+            Some(json!({"packages": {"run": {"requires": {
+                "pkg:pgxn/hstore": 0,
+                "pkg:postgres/citext": 0,
+            }}}})),
+            vec!["pkg:pgxn/hstore"],
+        ),
+        (
+            "postgres non-core",
+            Some(json!({"packages": {"build": {"recommends": {
+                "pkg:postgres/semver@1.0.0": 0,
+            }}}})),
+            vec!["pkg:postgres/semver@1.0.0"],
+        ),
+        (
+            "variations",
+            Some(json!({"variations": [{
+                "where": {"platforms": ["linux"]},
+                "dependencies": {"packages": {"test": {"requires": {
+                    "pkg:pgxn/pgtap": 0,
+                    "pkg:pgxn/pg_trgm": 0,
+                }}}},
+            }]})),
+            vec!["pkg:pgxn/pg_trgm"],
+        ),
+    ] {
+        let mut meta = meta.clone();
+        if let Some(deps) = deps {
+            meta["dependencies"] = deps;
+        }
+        let dist = Distribution::try_from(meta)?;
+        assert_eq!(exp, dist.miscategorized_dependencies(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_artifact_manifest() -> Result<(), Error> {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "corpus",
+        "v2",
+        "postgresml.json",
+    ]
+    .iter()
+    .collect();
+    let meta: Value = serde_json::from_reader(File::open(path)?)?;
+    let exp: String = meta["artifacts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| {
+            format!(
+                "{}  {}\n",
+                a["sha512"].as_str().unwrap(),
+                a["url"].as_str().unwrap()
+            )
+        })
+        .collect();
+    let dist = Distribution::try_from(meta)?;
+    assert_eq!(exp, dist.artifact_manifest()?);
+    assert_eq!(2, exp.lines().count());
+
+    // Test no artifacts, SHA-256 fallback, and no digest.
+    let meta = minimal_v2()?;
+    let dist: Distribution = serde_json::from_value(meta.clone())?;
+    assert_eq!("", dist.artifact_manifest()?);
+
+    let url = "https://example.com/pair-0.1.8.zip";
+    let sha256 = "2b9d2416096d2930be51e5332b70bcd97846947777a93e4a3d65fe1b5fd7b004";
+    let mut with_sha256 = meta.clone();
+    with_sha256["artifacts"] = json!([{"type": "source", "url": url, "sha256": sha256}]);
+    let dist: Distribution = serde_json::from_value(with_sha256)?;
+    assert_eq!(format!("{sha256}  {url}\n"), dist.artifact_manifest()?);
+
+    let mut no_digest = meta;
+    no_digest["artifacts"] = json!([{"type": "source", "url": url}]);
+    let dist: Distribution = serde_json::from_value(no_digest)?;
+    match dist.artifact_manifest() {
+        Ok(_) => panic!("missing digest unexpectedly succeeded"),
+        Err(e) => assert_eq!("artifact sha512 or sha256 property missing", e.to_string()),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_ignore_matcher() -> Result<(), Error> {
+    let mut meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "extensions": {
+          "pair": { "sql": "sql/pair.sql", "control": "pair.control" }
+        }
+      },
+      "meta-spec": { "version": "2.0.0" }
+    });
+
+    // No ignore list.
+    let dist = Distribution::try_from(meta.clone())?;
+    assert!(dist.ignore_matcher()?.is_none());
+
+    meta["ignore"] = json!(["*.tmp", "/.git", "doc/*.html"]);
+    let dist = Distribution::try_from(meta)?;
+    let ignore = dist.ignore_matcher()?.unwrap();
+    for (path, exp) in [
+        ("foo.tmp", true),
+        ("src/foo.tmp", true),
+        ("/.git", true),
+        (".git/config", true),
+        ("src/.git", false),
+        ("doc/pair.html", true),
+        ("src/doc/pair.html", false),
+        ("src/pair.c", false),
+    ] {
+        assert_eq!(exp, ignore.matches(RelativePath::new(path)), "{path}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_suspicious_ignore_globs() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    let dist = Distribution::try_from(meta.clone())?;
+    assert!(dist.suspicious_ignore_globs().is_empty());
+
+    meta["ignore"] = json!([
+        "/.git",
+        "*.tmp",
+        "/Users/theory/secret",
+        "/home/theory",
+        "/home",
+        "/tmp/",
+        "/src/home/x",
+        "home/theory",
+        "~/.pgpass",
+        "C:\\\\Users\\\\theory",
+        "d:/secret",
+        "c:secret",
+    ]);
+    let dist = Distribution::try_from(meta)?;
+    assert_eq!(
+        vec![
+            "/Users/theory/secret",
+            "/home/theory",
+            "~/.pgpass",
+            "C:\\\\Users\\\\theory",
+            "d:/secret",
+        ],
+        dist.suspicious_ignore_globs(),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_canonical_license() -> Result<(), Error> {
+    let meta = minimal_v2()?;
+
+    for (exp, licenses) in [
+        (
+            "PostgreSQL",
+            vec!["PostgreSQL", "(PostgreSQL)", "((PostgreSQL))"],
+        ),
+        (
+            "MIT OR PostgreSQL",
+            vec![
+                "MIT OR PostgreSQL",
+                "MIT   OR  PostgreSQL",
+                "(MIT OR PostgreSQL)",
+                "(MIT) OR (PostgreSQL)",
+                "mit or PostgreSQL",
+                "MIT/PostgreSQL",
+            ],
+        ),
+        (
+            "MIT OR Apache-2.0 AND PostgreSQL",
+            vec![
+                "MIT OR (Apache-2.0 AND PostgreSQL)",
+                "(MIT) OR Apache-2.0 AND (PostgreSQL)",
+            ],
+        ),
+        (
+            "(MIT OR Apache-2.0) AND PostgreSQL",
+            vec![
+                "(MIT OR Apache-2.0) AND PostgreSQL",
+                "((MIT OR Apache-2.0)) AND PostgreSQL",
+            ],
+        ),
+        (
+            "MIT OR Apache-2.0 OR PostgreSQL",
+            vec![
+                "MIT OR (Apache-2.0 OR PostgreSQL)",
+                "(MIT OR Apache-2.0) OR PostgreSQL",
+            ],
+        ),
+        (
+            "Apache-2.0 WITH LLVM-exception OR MIT",
+            vec![
+                "(Apache-2.0 WITH LLVM-exception) OR MIT",
+                "Apache-2.0  with LLVM-exception or MIT",
+            ],
+        ),
+        (
+            "GPL-3.0-or-later AND LGPL-2.1-only",
+            vec![
+                "GPL-3.0+ AND LGPL-2.1-only",
+                "(GPL-3.0-or-later) AND (LGPL-2.1-only)",
+            ],
+        ),
+    ] {
+        for license in licenses {
+            let mut meta = meta.clone();
+            meta["license"] = json!(license);
+            let dist: Distribution = serde_json::from_value(meta)?;
+            assert_eq!(exp, dist.canonical_license()?, "{license}");
+        }
+    }
+
+    // Make sure we get an error for an invalid license.
+    let mut meta = meta.clone();
+    meta["license"] = json!("NOT A LICENSE");
+    let dist: Distribution = serde_json::from_value(meta)?;
+    match dist.canonical_license() {
+        Ok(_) => panic!("invalid license unexpectedly succeeded"),
+        Err(e) => assert!(matches!(e, Error::License { .. }), "{e}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_unreachable_maintainers() -> Result<(), Error> {
+    let meta = minimal_v2()?;
+
+    for (name, maintainers, exp) in [
+        (
+            "reachable",
+            json!([
+                { "name": "theory", "email": "theory@pgxn.org" },
+                { "name": "Tembo", "url": "https://tembo.io" },
+            ]),
+            vec![],
+        ),
+        (
+            "unreachable",
+            json!([
+                { "name": "theory", "email": "theory@pgxn.org" },
+                { "name": "anon" },
+                { "name": "nobody" },
+            ]),
+            vec!["anon", "nobody"],
+        ),
+    ] {
+        let mut meta = meta.clone();
+        meta["maintainers"] = maintainers;
+        // Deserialize without validation, since v2 requires email or url.
+        let dist: Distribution = serde_json::from_value(meta)?;
+        assert_eq!(exp, dist.unreachable_maintainers(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_dependency_facts() -> Result<(), Error> {
+    // Test the run requirements converted from v1.
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus", "v1", "widget.json"]
+        .iter()
+        .collect();
+    let dist = Distribution::load(path)?;
+    let facts = dist.dependency_facts();
+    assert_eq!("widget", facts.name());
+    assert_eq!(dist.version(), facts.version());
+    assert_eq!(Some("8.0.0"), facts.postgres());
+    assert!(facts.facts().iter().any(|f| f.phase() == "run"
+        && f.relation() == "requires"
+        && f.purl() == "pkg:postgres/plpgsql"
+        && f.range() == &VersionRange::Integer(0)));
+
+    // Test filtering and serialization.
+    let mut meta = minimal_v2()?;
+    meta["dependencies"] = json!({
+        "postgres": { "version": "14.0" },
+        "packages": {
+            "build": { "requires": { "pkg:generic/python": "3.0" } },
+            "run": {
+                "requires": { "pkg:pgxn/semver": "1.0.0" },
+                "conflicts": { "pkg:pgxn/pair": 0 },
+            },
+            "test": { "requires": { "pkg:pgxn/pgtap": 0 } },
+        },
+    });
+    let dist = Distribution::try_from(meta)?;
+    assert_eq!(
+        json!({
+            "name": "pair",
+            "version": "0.1.8",
+            "postgres": "14.0",
+            "facts": [
+                {"phase": "build", "relation": "requires", "purl": "pkg:generic/python", "range": "3.0"},
+                {"phase": "run", "relation": "conflicts", "purl": "pkg:pgxn/pair", "range": 0},
+                {"phase": "run", "relation": "requires", "purl": "pkg:pgxn/semver", "range": "1.0.0"},
+            ],
+        }),
+        serde_json::to_value(dist.dependency_facts())?,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_suspicious_extension_layout() -> Result<(), Error> {
+    for (name, json, exp) in [
+        ("no extensions", json!({}), vec![]),
+        (
+            "same directory",
+            json!({"extensions": {
+              "pair": { "sql": "sql/pair.sql", "control": "sql/pair.control" },
+            }}),
+            vec![],
+        ),
+        (
+            "shallow",
+            json!({"extensions": {
+              "pair": { "sql": "sql/pair.sql", "control": "pair.control" },
+            }}),
+            vec![],
+        ),
+        (
+            "converted from v1",
+            json!({"extensions": {
+              "pair": { "sql": "src/sql/pair.sql", "control": "pair.control" },
+            }}),
+            vec![(
+                "pair",
+                "control file in root directory but sql file 2 directories deep",
+            )],
+        ),
+        (
+            "deep control",
+            json!({"extensions": {
+              "pair": { "sql": "pair.sql", "control": "ext/pair/pair.control" },
+              "trio": { "sql": "sql/trio.sql", "control": "trio.control" },
+            }}),
+            vec![(
+                "pair",
+                "sql file in root directory but control file 2 directories deep",
+            )],
+        ),
+    ] {
+        let contents: Contents = serde_json::from_value(json)?;
+        let exp: Vec<(String, String)> = exp
+            .into_iter()
+            .map(|(n, r)| (n.to_string(), r.to_string()))
+            .collect();
+        assert_eq!(exp, contents.suspicious_extension_layout(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_path_roles() -> Result<(), Error> {
+    let meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "extensions": {
+          "pair": { "sql": "sql/pair.sql", "control": "pair.control", "doc": "doc/pair.md" }
+        },
+        "modules": {
+          "pair": { "type": "extension", "lib": "src/pair" }
+        },
+        "apps": {
+          "pair_dump": {
+            "bin": "bin/pair_dump",
+            "lib": "lib/pair_dump",
+            "doc": "doc/pair_dump.md",
+            "man": "man/pair_dump.1",
+            "html": "html/pair_dump.html"
+          }
+        }
+      },
+      "meta-spec": { "version": "2.0.0" }
+    });
+    let dist = Distribution::try_from(meta)?;
+
+    assert_eq!(
+        vec![
+            (
+                "pair".to_string(),
+                "control",
+                RelativePath::new("pair.control")
+            ),
+            ("pair".to_string(), "doc", RelativePath::new("doc/pair.md")),
+            ("pair".to_string(), "lib", RelativePath::new("src/pair")),
+            ("pair".to_string(), "sql", RelativePath::new("sql/pair.sql")),
+            (
+                "pair_dump".to_string(),
+                "bin",
+                RelativePath::new("bin/pair_dump")
+            ),
+            (
+                "pair_dump".to_string(),
+                "doc",
+                RelativePath::new("doc/pair_dump.md")
+            ),
+            (
+                "pair_dump".to_string(),
+                "html",
+                RelativePath::new("html/pair_dump.html")
+            ),
+            (
+                "pair_dump".to_string(),
+                "lib",
+                RelativePath::new("lib/pair_dump")
+            ),
+            (
+                "pair_dump".to_string(),
+                "man",
+                RelativePath::new("man/pair_dump.1")
+            ),
+        ],
+        dist.path_roles(),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_from_value_lenient() -> Result<(), Error> {
+    let meta = json!({
+      "name": "pair",
+      "abstract": "A key/value pair data type",
+      "version": "0.1.8",
+      "maintainers": [{ "name": "theory", "email": "theory@pgxn.org" }],
+      "license": "PostgreSQL",
+      "contents": {
+        "modules": {
+          "pair": { "type": "extension", "lib": "src/pair" }
+        }
+      },
+      "meta-spec": { "version": "2.0.0" }
+    });
+
+    // Known values produce no warnings.
+    let (dist, warnings) = Distribution::from_value_lenient(meta.clone())?;
+    assert!(warnings.is_empty());
+    assert_eq!(Distribution::try_from(meta.clone())?, dist);
+
+    // Unknown values are tolerated and reported.
+    let mut meta = meta;
+    json_patch::merge(
+        &mut meta,
+        &json!({
+            "contents": {"modules": {"pair": {"type": "planner", "preload": "cluster"}}},
+            "dependencies": {
+                "pipeline": "bazel",
+                "variations": [{
+                    "where": {"platforms": ["linux"]},
+                    "dependencies": {"pipeline": "pgxs"},
+                }],
+            },
+        }),
+    );
+    let (dist, warnings) = Distribution::from_value_lenient(meta.clone())?;
+    assert_eq!(
+        vec![
+            "dependencies: unknown pipeline \"bazel\"",
+            "module pair: unknown preload \"cluster\"",
+            "module pair: unknown type \"planner\"",
+        ],
+        warnings,
+    );
+    let module = &dist.contents().modules().unwrap()["pair"];
+    assert_eq!(&ModuleType::Other("planner".to_string()), module.kind());
+    assert_eq!("planner", module.kind().to_string());
+    assert_eq!(
+        Some(&Preload::Other("cluster".to_string())),
+        module.preload()
+    );
+    assert_eq!(
+        Some(&Pipeline::Other("bazel".to_string())),
+        dist.dependencies().unwrap().pipeline()
+    );
+
+    // Unknown values round-trip.
+    let val: Value = dist.try_into()?;
+    assert_eq!(json!("planner"), val["contents"]["modules"]["pair"]["type"]);
+    assert_eq!(json!("bazel"), val["dependencies"]["pipeline"]);
+
+    // v1 metadata is converted.
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus", "v1", "widget.json"]
+        .iter()
+        .collect();
+    let v1: Value = serde_json::from_reader(File::open(path)?)?;
+    let (dist, warnings) = Distribution::from_value_lenient(v1.clone())?;
+    assert!(warnings.is_empty());
+    assert_eq!(Distribution::try_from(v1)?, dist);
+
+    // The strict path still rejects unknown values.
+    if Distribution::try_from(meta).is_ok() {
+        panic!("unknown module type unexpectedly passed validation");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_dependency_count() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+
+    // No dependencies.
+    let dist = Distribution::try_from(meta.clone())?;
+    assert_eq!(0, dist.dependency_count());
+    assert_eq!(None, dist.postgres_requirement());
+
+    // Test the everything dependencies fixture.
+    meta["dependencies"] = json!({
+        "postgres": {"version": "14", "with": ["xml"]},
+        "platforms": ["linux", "darwin"],
+        "pipeline": "pgrx",
+        "packages": {
+            "configure": { "requires": { "pkg:generic/cmake": 0 } },
+            "build": { "recommends": { "pkg:generic/jq": 0 } },
+            "test": { "requires": { "pkg:pgxn/pgtap": "1.0.0" } },
+            "run": { "suggests": { "pkg:postgres/hstore": 0 } },
+            "develop": { "suggests": { "pkg:generic/python": 0 } },
+        },
+        "variations": [
+            {
+                "where": { "platforms": ["darwin", "bsd"] },
+                "dependencies": {"postgres": {"version": "14"}},
+            },
+        ]
+    });
+    let dist = Distribution::try_from(meta.clone())?;
+    assert_eq!(5, dist.dependency_count());
+    assert_eq!(Some("14"), dist.postgres_requirement());
+
+    // Variations count only purls not declared elsewhere.
+    meta["dependencies"]["variations"] = json!([{
+        "where": { "platforms": ["darwin"] },
+        "dependencies": {
+            "postgres": {"version": "16"},
+            "packages": {
+                "build": { "requires": { "pkg:generic/jq": "1.6" } },
+                "run": { "requires": { "pkg:generic/zstd": 0 } },
+            },
+        },
+    }]);
+    let dist = Distribution::try_from(meta)?;
+    assert_eq!(6, dist.dependency_count());
+    assert_eq!(Some("14"), dist.postgres_requirement());
+
+    Ok(())
+}
+
+#[test]
+fn test_producer_tool() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    let dist = Distribution::try_from(meta.clone())?;
+    assert_eq!(None, dist.producer_tool());
+
+    for (name, producer, exp) in [
+        (
+            "space version",
+            "pgrx 0.11.4",
+            Some(("pgrx", Some("0.11.4"))),
+        ),
+        ("slash version", "tool/1.2.3", Some(("tool", Some("1.2.3")))),
+        (
+            "v version",
+            "pgxn_meta v0.6.0",
+            Some(("pgxn_meta", Some("v0.6.0"))),
+        ),
+        (
+            "prerelease",
+            "pgrx 0.12.0-beta.1",
+            Some(("pgrx", Some("0.12.0-beta.1"))),
+        ),
+        ("padded", "  pgrx 0.11.4 ", Some(("pgrx", Some("0.11.4")))),
+        (
+            "perl module",
+            "PGXN::Meta/0.16",
+            Some(("PGXN::Meta", Some("0.16"))),
+        ),
+        ("bare tool", "pgxn-tools", Some(("pgxn-tools", None))),
+        ("human", "David E. Wheeler", None),
+        ("first last", "Jane Doe", None),
+        ("capitalized word", "Theory", None),
+        ("email", "david@example.com", None),
+        ("versionless slash", "tool/latest", None),
+        ("empty slash name", "/1.2.3", None),
+        ("numeric name", "42 1.0", None),
+    ] {
+        meta["producer"] = json!(producer);
+        let dist: Distribution = serde_json::from_value(meta.clone())?;
+        assert_eq!(exp, dist.producer_tool(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_contents_eq_normalized() -> Result<(), Error> {
+    let contents = json!({
+        "extensions": {
+            "pair": {"control": "pair.control", "sql": "sql/pair.sql", "doc": "doc/pair.md"},
+        },
+        "modules": {"pair": {"type": "hook", "lib": "lib/pair"}},
+        "apps": {"pair": {"bin": "bin/pair", "man": "man/pair.1"}},
+    });
+    let dotted = json!({
+        "extensions": {
+            "pair": {"control": "./pair.control", "sql": "./sql/pair.sql", "doc": "doc/./pair.md"},
+        },
+        "modules": {"pair": {"type": "hook", "lib": "./lib/pair"}},
+        "apps": {"pair": {"bin": "./bin/pair", "man": "./man/pair.1"}},
+    });
+    let a: Contents = serde_json::from_value(contents.clone())?;
+    let b: Contents = serde_json::from_value(dotted)?;
+    assert_ne!(a, b);
+    assert!(a.eq_normalized(&b));
+    assert!(b.eq_normalized(&a));
+    assert!(a.eq_normalized(&a));
+
+    // Differences other than path spelling are not equal.
+    for (name, patch) in [
+        (
+            "sql path",
+            json!({"extensions": {"pair": {"sql": "./sql/pair--1.0.sql"}}}),
+        ),
+        (
+            "abstract",
+            json!({"extensions": {"pair": {"abstract": "Pairs"}}}),
+        ),
+        (
+            "missing doc",
+            json!({"extensions": {"pair": {"doc": null}}}),
+        ),
+        ("module type", json!({"modules": {"pair": {"type": "bgw"}}})),
+        ("app html", json!({"apps": {"pair": {"html": "./html"}}})),
+        (
+            "extra app",
+            json!({"apps": {"widget": {"bin": "bin/widget"}}}),
+        ),
+        ("no modules", json!({"modules": null})),
+        ("custom", json!({"x_y": 1})),
+    ] {
+        let mut other = contents.clone();
+        json_patch::merge(&mut other, &patch);
+        let other: Contents = serde_json::from_value(other)?;
+        assert!(!a.eq_normalized(&other), "{name}");
+        assert!(!other.eq_normalized(&a), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_requires_restart() -> Result<(), Error> {
+    let meta = minimal_v2()?;
+
+    for (name, modules, exp) in [
+        ("no modules", json!(null), vec![]),
+        (
+            "server",
+            json!({"pair": {"type": "hook", "lib": "lib/pair", "preload": "server"}}),
+            vec!["pair"],
+        ),
+        (
+            "session",
+            json!({"pair": {"type": "hook", "lib": "lib/pair", "preload": "session"}}),
+            vec![],
+        ),
+        (
+            "no preload",
+            json!({"pair": {"type": "extension", "lib": "lib/pair"}}),
+            vec![],
+        ),
+        (
+            "mixed",
+            json!({
+                "zebra": {"type": "bgw", "lib": "lib/zebra", "preload": "server"},
+                "pair": {"type": "hook", "lib": "lib/pair", "preload": "session"},
+                "ant": {"type": "hook", "lib": "lib/ant", "preload": "server"},
+            }),
+            vec!["ant", "zebra"],
+        ),
+    ] {
+        let mut meta = meta.clone();
+        json_patch::merge(&mut meta, &json!({"contents": {"modules": modules}}));
+        let dist = Distribution::try_from(meta)?;
+        assert_eq!(exp, dist.server_preload_modules(), "{name}");
+        assert_eq!(!exp.is_empty(), dist.requires_restart(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_tle_with_native_code() -> Result<(), Error> {
+    let meta = minimal_v2()?;
+
+    for (name, patch, exp) in [
+        ("not tle", json!({}), vec![]),
+        (
+            "tle only",
+            json!({"contents": {"extensions": {"pair": {"tle": true}}}}),
+            vec![],
+        ),
+        (
+            "tle with module",
+            json!({"contents": {
+                "extensions": {"pair": {"tle": true}},
+                "modules": {"pair": {"type": "hook", "lib": "lib/pair"}},
+            }}),
+            vec!["pair"],
+        ),
+        (
+            "tle with other module",
+            json!({"contents": {
+                "extensions": {"pair": {"tle": true}},
+                "modules": {"pair_hook": {"type": "hook", "lib": "lib/pair_hook"}},
+            }}),
+            vec![],
+        ),
+        (
+            "non-tle with module",
+            json!({"contents": {
+                "modules": {"pair": {"type": "extension", "lib": "lib/pair"}},
+            }}),
+            vec![],
+        ),
+    ] {
+        let mut meta = meta.clone();
+        json_patch::merge(&mut meta, &patch);
+        let dist = Distribution::try_from(meta)?;
+        assert_eq!(exp, dist.tle_with_native_code(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_display() -> Result<(), Error> {
+    let dist = Distribution::try_from(crate::tests::v2::valid_v2_distribution())?;
+    let str = dist.to_string();
+    for part in ["pgTAP", "0.26.0", "MIT OR PostgreSQL"] {
+        assert!(str.contains(part), "{str} missing {part}");
+    }
+    assert_eq!(
+        "pgTAP 0.26.0 — Unit testing for PostgreSQL (MIT OR PostgreSQL)",
+        str,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_stable_serialization() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    json_patch::merge(
+        &mut meta,
+        &json!({
+            "contents": {
+                "extensions": {
+                    "zebra": {"sql": "sql/zebra.sql", "control": "zebra.control"},
+                    "ant": {"sql": "sql/ant.sql", "control": "ant.control"},
+                    "moose": {"sql": "sql/moose.sql", "control": "moose.control"},
+                },
+                "modules": {
+                    "yak": {"type": "hook", "lib": "lib/yak"},
+                    "bee": {"type": "hook", "lib": "lib/bee"},
+                },
+                "x_zz": true,
+                "x_aa": false,
+            },
+            "dependencies": {"packages": {"run": {"requires": {
+                "pkg:pgxn/zebra": 0,
+                "pkg:pgxn/ant": "1.0.0",
+            }}}},
+            "x_zz": 1,
+            "x_aa": 2,
+        }),
+    );
+
+    // Deserialize twice and make sure both serialize identically.
+    let one = serde_json::to_string(&Distribution::try_from(meta.clone())?)?;
+    let two = serde_json::to_string(&Distribution::try_from(meta)?)?;
+    assert_eq!(one, two);
+
+    // Keys should be sorted.
+    for (first, second) in [
+        ("\"ant\"", "\"moose\""),
+        ("\"moose\"", "\"zebra\""),
+        ("\"bee\"", "\"yak\""),
+        ("\"pkg:pgxn/ant\"", "\"pkg:pgxn/zebra\""),
+        ("\"x_aa\":false", "\"x_zz\":true"),
+        ("\"x_aa\":2", "\"x_zz\":1"),
+    ] {
+        assert!(
+            one.find(first).unwrap() < one.find(second).unwrap(),
+            "{first} should precede {second}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_content_fingerprint() -> Result<(), Error> {
+    let meta = minimal_v2()?;
+    let base = Distribution::try_from(meta.clone())?.content_fingerprint();
+    assert_eq!(64, base.len());
+    assert!(base.chars().all(|c| c.is_ascii_hexdigit()));
+
+    for (name, patch, same) in [
+        ("producer", json!({"producer": "pgxn-cli 1.0.0"}), true),
+        (
+            "spec url",
+            json!({"meta-spec": {"url": "https://rfcs.pgxn.org/0003-meta-spec-v2.html"}}),
+            true,
+        ),
+        ("custom prop", json!({"x_build": 42}), true),
+        (
+            "nested custom props",
+            json!({"contents": {
+                "x_note": "hi",
+                "extensions": {"pair": {"x_foo": true}},
+            }}),
+            true,
+        ),
+        (
+            "abstract",
+            json!({"abstract": "A pair of keys and values"}),
+            false,
+        ),
+        ("version", json!({"version": "0.1.9"}), false),
+        (
+            "custom-looking extension name",
+            json!({"contents": {"extensions": {"x_pair": {
+                "sql": "sql/x_pair.sql",
+                "control": "x_pair.control",
+            }}}}),
+            false,
+        ),
+    ] {
+        let mut meta = meta.clone();
+        json_patch::merge(&mut meta, &patch);
+        let print = Distribution::try_from(meta)?.content_fingerprint();
+        assert_eq!(same, base == print, "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sort_maintainers() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    meta["maintainers"] = json!([
+        {"name": "Josh", "email": "josh@example.com"},
+        {"name": "Anna", "url": "https://example.com/anna"},
+        {"name": "David", "email": "david@example.org"},
+        {"name": "Anna", "email": "anna@example.com"},
+        {"name": "David", "email": "david@example.com"},
+    ]);
+    let mut dist = Distribution::try_from(meta)?;
+    let keys = |m: &Maintainer| (m.name().to_string(), m.email().map(String::from));
+    let exp = vec![
+        ("Anna".to_string(), None),
+        ("Anna".to_string(), Some("anna@example.com".to_string())),
+        ("David".to_string(), Some("david@example.com".to_string())),
+        ("David".to_string(), Some("david@example.org".to_string())),
+        ("Josh".to_string(), Some("josh@example.com".to_string())),
+    ];
+
+    // sorted_maintainers should not change the stored order.
+    let sorted: Vec<_> = dist.sorted_maintainers().into_iter().map(keys).collect();
+    assert_eq!(exp, sorted);
+    assert_eq!("Josh", dist.maintainers()[0].name());
+
+    // sort_maintainers should.
+    dist.sort_maintainers();
+    let sorted: Vec<_> = dist.maintainers().iter().map(keys).collect();
+    assert_eq!(exp, sorted);
+    assert_eq!("Anna", dist.maintainers()[0].name());
+
+    Ok(())
+}
+
+#[test]
+fn test_primary_maintainer() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    meta["maintainers"] = json!([
+        {"name": "Josh", "email": "josh@example.com"},
+        {"name": "Anna", "email": "anna@example.com"},
+    ]);
+    let dist = Distribution::try_from(meta.clone())?;
+    let primary = dist.primary_maintainer().unwrap();
+    assert_eq!("Josh", primary.name());
+    assert_eq!(Some("josh@example.com"), primary.email());
+
+    // Skip validation to get an empty list.
+    meta["maintainers"] = json!([]);
+    let dist: Distribution = serde_json::from_value(meta)?;
+    assert!(dist.maintainers().is_empty());
+    assert_eq!(None, dist.primary_maintainer());
+
+    Ok(())
+}
+
+#[test]
+fn test_revalidate() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    let mut dist = Distribution::try_from(meta.clone())?;
+    assert_eq!(2, dist.revalidate()?);
+    dist.sort_maintainers();
+    assert_eq!(2, dist.revalidate()?);
+
+    // Converted v1 metadata revalidates as v2.
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus", "v1", "widget.json"]
+        .iter()
+        .collect();
+    let dist = Distribution::load(path)?;
+    assert_eq!(2, dist.revalidate()?);
+
+    // Skip validation to get an empty maintainers list.
+    meta["maintainers"] = json!([]);
+    let dist: Distribution = serde_json::from_value(meta)?;
+    match dist.revalidate() {
+        Ok(_) => panic!("Should have failed on empty maintainers"),
+        Err(e) => assert!(matches!(e, Error::ValidationError(_)), "{e}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_add_tags() -> Result<(), Error> {
+    let mut dist = Distribution::try_from(crate::tests::v2::valid_v2_distribution())?;
+    let prev: Vec<String> = dist.classifications().unwrap().tags().unwrap().to_vec();
+    dist.add_tags(&["xunit", "testing", "xunit"])?;
+    let tags = dist.classifications().unwrap().tags().unwrap();
+    assert_eq!(prev.len() + 1, tags.len());
+    assert!(prev.iter().all(|t| tags.contains(t)));
+    assert_eq!("xunit", tags.last().unwrap());
+
+    // Add to a Distribution without classifications.
+    let mut dist = Distribution::try_from(template("pair"))?;
+    dist.add_tags(&[])?;
+    assert!(dist.classifications().is_none());
+    dist.add_tags(&["pair", "key value"])?;
+    assert_eq!(
+        Some(&["pair".to_string(), "key value".to_string()][..]),
+        dist.classifications().unwrap().tags()
+    );
+    assert!(dist.classifications().unwrap().categories().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_add_categories() -> Result<(), Error> {
+    let mut dist = Distribution::try_from(crate::tests::v2::valid_v2_distribution())?;
+    let prev: Vec<String> = dist
+        .classifications()
+        .unwrap()
+        .categories()
+        .unwrap()
+        .to_vec();
+    dist.add_categories(&["Debugging", prev[0].as_str()])?;
+    let mut exp = prev.clone();
+    exp.push("Debugging".to_string());
+    assert_eq!(Some(&exp[..]), dist.classifications().unwrap().categories());
+
+    // Reject exceeding the limit of three.
+    match dist.add_categories(&["Analytics", "Metrics"]) {
+        Ok(_) => panic!("Should have failed on too many categories"),
+        Err(e) => assert!(matches!(e, Error::ValidationError(_)), "{e}"),
+    }
+    assert_eq!(Some(&exp[..]), dist.classifications().unwrap().categories());
+
+    // Reject an unknown category.
+    let mut dist = Distribution::try_from(template("pair"))?;
+    match dist.add_categories(&["Nonesuch"]) {
+        Ok(_) => panic!("Should have failed on unknown category"),
+        Err(e) => assert!(matches!(e, Error::ValidationError(_)), "{e}"),
+    }
+    assert!(dist.classifications().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_search_keywords() -> Result<(), Error> {
+    let dist = Distribution::try_from(crate::tests::v2::valid_v2_distribution())?;
+    let words = dist.search_keywords();
+    for want in [
+        "pgtap",
+        "testing",
+        "unit testing",
+        "tooling and admin",
+        "unit",
+        "postgresql",
+    ] {
+        assert!(
+            words.iter().any(|w| w == want),
+            "missing {want} in {words:?}"
+        );
+    }
+    assert!(words.is_sorted(), "{words:?}");
+    assert_eq!(1, words.iter().filter(|w| *w == "testing").count());
+
+    // Test tokenization and lowercasing.
+    let mut meta = crate::tests::v2::valid_v2_distribution();
+    json_patch::merge(
+        &mut meta,
+        &json!({
+            "abstract": "PL/pgSQL key_value, pair-type; V2!",
+            "classifications": null,
+        }),
+    );
+    let dist = Distribution::try_from(meta)?;
+    assert_eq!(
+        vec!["key_value", "pair-type", "pgsql", "pgtap", "pl", "v2"],
+        dist.search_keywords(),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_category_histogram() -> Result<(), Error> {
+    let dist = |name: &str, categories: Value| {
+        let mut meta = template(name);
+        meta["classifications"] = json!({"categories": categories});
+        Distribution::try_from(meta)
+    };
+    let dists = [
+        dist("pg_stats", json!(["Metrics"]))?,
+        dist("pg_probe", json!(["Metrics"]))?,
+        dist("pg_find", json!(["Search"]))?,
+        Distribution::try_from(template("pg_none"))?,
+    ];
+
+    let exp = BTreeMap::from([("Metrics".to_string(), 2), ("Search".to_string(), 1)]);
+    assert_eq!(exp, category_histogram(&dists));
+    assert!(category_histogram([]).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_name_issues() -> Result<(), Error> {
+    for (name, dist, ext, exp) in [
+        ("clean", "pair", "pair", vec![]),
+        ("digits", "123", "pair", vec!["name 123 is all digits"]),
+        (
+            "case",
+            "Pair",
+            "pair",
+            vec!["name Pair differs from extension pair only by case"],
+        ),
+        (
+            "leading hyphen",
+            "-pair",
+            "pair",
+            vec!["name -pair starts with a hyphen"],
+        ),
+        (
+            "trailing hyphen",
+            "pair-",
+            "pair",
+            vec!["name pair- ends with a hyphen"],
+        ),
+        (
+            "several",
+            "-PAIR-",
+            "-pair-",
+            vec![
+                "name -PAIR- differs from extension -pair- only by case",
+                "name -PAIR- starts with a hyphen",
+                "name -PAIR- ends with a hyphen",
+            ],
+        ),
+    ] {
+        let mut meta = template(ext);
+        meta["name"] = json!(dist);
+        let dist = Distribution::try_from(meta)?;
+        assert_eq!(exp, dist.name_issues(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_postgres_supported_majors() -> Result<(), Error> {
+    let known = [12, 13, 14, 15, 16, 17, 18];
+    for (name, version, exp) in [
+        ("zero string", json!("0"), vec![12, 13, 14, 15, 16, 17, 18]),
+        ("majors", json!(">=14, <18"), vec![14, 15, 16, 17]),
+        (
+            "minor upper",
+            json!(">=14.0, <18.1"),
+            vec![14, 15, 16, 17, 18],
+        ),
+        ("minor lower", json!(">= 14.2, <= 16.0"), vec![14, 15, 16]),
+        ("bare", json!("14"), vec![14, 15, 16, 17, 18]),
+        ("bare minor", json!("14.0"), vec![14, 15, 16, 17, 18]),
+        ("patch", json!("9.6.3"), vec![12, 13, 14, 15, 16, 17, 18]),
+        ("equal", json!("==16"), vec![16]),
+        ("equal minor", json!("== 16.2"), vec![16]),
+        ("not equal", json!(">= 14, != 15"), vec![14, 16, 17, 18]),
+        (
+            "not equal minor",
+            json!(">= 14, != 15.1"),
+            vec![14, 15, 16, 17, 18],
+        ),
+        ("greater", json!("> 17"), vec![18]),
+        ("less or equal", json!("<= 13"), vec![12, 13]),
+        ("prerelease", json!(">= 18.0-beta1"), vec![18]),
+        (
+            "before prerelease",
+            json!("< 18.0-beta1"),
+            vec![12, 13, 14, 15, 16, 17, 18],
+        ),
+        ("none", json!(">= 20"), vec![]),
+        ("contradiction", json!(">= 16, < 15"), vec![]),
+    ] {
+        let pg: Postgres = serde_json::from_value(json!({"version": version}))?;
+        assert_eq!(exp, pg.supported_majors(&known)?, "{name}");
+    }
+
+    // Test errors.
+    let pg: Postgres = serde_json::from_value(json!({"version": ">= fourteen"}))?;
+    match pg.supported_majors(&known) {
+        Ok(_) => panic!("invalid range unexpectedly succeeded"),
+        Err(e) => assert_eq!(
+            "invalid v2 postgres version value: \">= fourteen\"",
+            e.to_string()
+        ),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_postgres_unknown_with_options() -> Result<(), Error> {
+    for (name, with, known, exp) in [
+        ("none", None, POSTGRES_WITH_OPTIONS, vec![]),
+        (
+            "known",
+            Some(json!(["xml", "zstd"])),
+            POSTGRES_WITH_OPTIONS,
+            vec![],
+        ),
+        (
+            "typo",
+            Some(json!(["xml", "xlm", "zstd"])),
+            POSTGRES_WITH_OPTIONS,
+            vec!["xlm"],
+        ),
+        (
+            "case",
+            Some(json!(["XML"])),
+            POSTGRES_WITH_OPTIONS,
+            vec!["XML"],
+        ),
+        (
+            "custom",
+            Some(json!(["xml", "postgis"])),
+            &["postgis"][..],
+            vec!["xml"],
+        ),
+        ("empty known", Some(json!(["ssl"])), &[][..], vec!["ssl"]),
+    ] {
+        let mut meta = json!({"version": "14"});
+        if let Some(with) = with {
+            meta["with"] = with;
+        }
+        let pg: Postgres = serde_json::from_value(meta)?;
+        assert_eq!(exp, pg.unknown_with_options(known), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_postgres_parsed_version() -> Result<(), Error> {
+    for (name, range, matches, misses) in [
+        ("zero", "0", vec!["9.6.0", "18.1.0"], vec![]),
+        ("major", "14", vec!["14.0.0", "17.2.0"], vec!["13.9.0"]),
+        (
+            "bounded",
+            ">=14.0, <18.1",
+            vec!["14.0.0", "18.0.5"],
+            vec!["13.99.0", "18.1.0"],
+        ),
+        ("exact", "== 16.2", vec!["16.2.0"], vec!["16.3.0", "16.1.0"]),
+        (
+            "less than",
+            "<= 12",
+            vec!["9.6.0", "12.0.0", "12.1.0"],
+            vec!["13.0.0"],
+        ),
+    ] {
+        let pg: Postgres = serde_json::from_value(json!({"version": range}))?;
+        let req = pg.parsed_version()?;
+        for v in matches {
+            assert!(
+                req.matches(&Version::parse(v).unwrap()),
+                "{name} matches {v}"
+            );
+        }
+        for v in misses {
+            assert!(
+                !req.matches(&Version::parse(v).unwrap()),
+                "{name} misses {v}"
+            );
+        }
+    }
+
+    for (name, range) in [
+        ("invalid", "x.y.z"),
+        ("invalid clause", ">= 14, <= x"),
+        ("not equal", "!= 15"),
+    ] {
+        let pg: Postgres = serde_json::from_value(json!({"version": range}))?;
+        match pg.parsed_version() {
+            Ok(_) => panic!("{name} unexpectedly parsed"),
+            Err(e) => assert_eq!(
+                format!("invalid v2 postgres version value: \"{range}\""),
+                e.to_string(),
+                "{name}"
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_maintainer_has_fallback_url() -> Result<(), Error> {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus", "v1", "widget.json"]
+        .iter()
+        .collect();
+    let mut meta: Value = serde_json::from_reader(File::open(path)?)?;
+    meta["maintainer"] = json!(["David E. Wheeler <theory@pgxn.org>", "Josh Berkus"]);
+    meta.as_object_mut().unwrap().remove("resources");
+
+    // Without a homepage, the maintainer with no email gets the fallback.
+    let dist = Distribution::try_from(meta.clone())?;
+    let maintainers = dist.maintainers();
+    assert!(!maintainers[0].has_fallback_url());
+    assert_eq!("Josh Berkus", maintainers[1].name());
+    assert_eq!(Some(v1::FALLBACK_MAINTAINER_URL), maintainers[1].url());
+    assert!(maintainers[1].has_fallback_url());
+
+    // With a homepage, it gets the homepage.
+    meta["resources"] = json!({"homepage": "https://example.com/widget"});
+    let dist = Distribution::try_from(meta)?;
+    assert!(!dist.maintainers()[1].has_fallback_url());
+
+    Ok(())
+}
+
+#[test]
+fn test_maintainer_pgxn_handle() {
+    for (name, url, exp) in [
+        ("pgxn", Some("https://pgxn.org/user/theory"), Some("theory")),
+        (
+            "trailing slash",
+            Some("https://pgxn.org/user/theory/"),
+            Some("theory"),
+        ),
+        ("github", Some("https://github.com/theory"), None),
+        ("no handle", Some("https://pgxn.org/user/"), None),
+        ("sub-path", Some("https://pgxn.org/user/theory/dists"), None),
+        ("query", Some("https://pgxn.org/user/theory?x=1"), None),
+        ("no url", None, None),
+    ] {
+        let m: Maintainer = serde_json::from_value(json!({
+            "name": "David E. Wheeler",
+            "url": url,
+        }))
+        .unwrap();
+        assert_eq!(exp, m.pgxn_handle(), "{name}");
+    }
+}
+
+#[test]
+fn test_insecure_badges() -> Result<(), Error> {
+    let resources: Resources = serde_json::from_value(json!({"homepage": "https://example.com"}))?;
+    assert!(resources.insecure_badges().is_empty());
+
+    for (name, badge, insecure) in [
+        (
+            "https",
+            json!({"src": "https://example.com/b.svg", "alt": "b", "url": "https://example.com"}),
+            false,
+        ),
+        (
+            "https no url",
+            json!({"src": "https://example.com/b.svg", "alt": "b"}),
+            false,
+        ),
+        (
+            "http src",
+            json!({"src": "http://example.com/b.svg", "alt": "b"}),
+            true,
+        ),
+        (
+            "http url",
+            json!({"src": "https://example.com/b.svg", "alt": "b", "url": "http://example.com"}),
+            true,
+        ),
+        (
+            "HTTP src",
+            json!({"src": "HTTP://example.com/b.svg", "alt": "b"}),
+            true,
+        ),
+        (
+            "HTTPS src",
+            json!({"src": "HTTPS://example.com/b.svg", "alt": "b"}),
+            false,
+        ),
+        (
+            "ftp src",
+            json!({"src": "ftp://example.com/b.svg", "alt": "b"}),
+            true,
+        ),
+        (
+            "mailto url",
+            json!({"src": "https://example.com/b.svg", "alt": "b", "url": "mailto:x@example.com"}),
+            false,
+        ),
+    ] {
+        let resources: Resources = serde_json::from_value(json!({"badges": [badge]}))?;
+        let exp: Vec<&Badge> = match insecure {
+            true => resources.badges().unwrap().iter().collect(),
+            false => vec![],
+        };
+        assert_eq!(exp, resources.insecure_badges(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_repository_info() -> Result<(), Error> {
+    for (name, url, exp) in [
+        (
+            "github",
+            "https://github.com/theory/pgtap",
+            Some(("github.com", "theory", "pgtap")),
+        ),
+        (
+            "git suffix",
+            "https://github.com/theory/pgtap.git",
+            Some(("github.com", "theory", "pgtap")),
+        ),
+        (
+            "trailing slash",
+            "https://github.com/theory/pgtap/",
+            Some(("github.com", "theory", "pgtap")),
+        ),
+        (
+            "subpath",
+            "https://github.com/theory/pgtap/tree/main",
+            Some(("github.com", "theory", "pgtap")),
+        ),
+        (
+            "www",
+            "http://www.GitHub.com/theory/pgtap",
+            Some(("github.com", "theory", "pgtap")),
+        ),
+        (
+            "ssh",
+            "ssh://git@github.com/theory/pgtap.git",
+            Some(("github.com", "theory", "pgtap")),
+        ),
+        (
+            "codeberg",
+            "https://codeberg.org/theory/pair",
+            Some(("codeberg.org", "theory", "pair")),
+        ),
+        (
+            "gitlab",
+            "https://gitlab.com/theory/pair",
+            Some(("gitlab.com", "theory", "pair")),
+        ),
+        (
+            "gitlab subgroup",
+            "https://gitlab.com/pg/tools/pair/-/tree/main",
+            Some(("gitlab.com", "pg/tools", "pair")),
+        ),
+        ("owner only", "https://github.com/theory", None),
+        ("no path", "https://github.com", None),
+        ("other host", "https://example.com/theory/pgtap", None),
+        ("not a url", "x:y", None),
+    ] {
+        let res: Resources = serde_json::from_value(json!({"repository": url}))?;
+        let info = res.repository_info();
+        assert_eq!(
+            exp,
+            info.as_ref().map(|i| (i.host(), i.owner(), i.name())),
+            "{name}"
+        );
+    }
+
+    // No repository.
+    let res: Resources = serde_json::from_value(json!({"homepage": "https://pgtap.org"}))?;
+    assert_eq!(None, res.repository_info());
+
+    Ok(())
+}
+
+#[test]
+fn test_description_duplicates_abstract() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    meta["abstract"] = json!("A key/value pair data type");
+
+    for (name, description, exp) in [
+        ("none", json!(null), false),
+        ("identical", json!("A key/value pair data type"), true),
+        ("whitespace", json!("  A key/value pair data type\n"), true),
+        (
+            "different",
+            json!("This library contains a key/value pair data type."),
+            false,
+        ),
+        ("case", json!("a key/value pair data type"), false),
+    ] {
+        let mut meta = meta.clone();
+        json_patch::merge(&mut meta, &json!({"description": description}));
+        let dist = Distribution::try_from(meta)?;
+        assert_eq!(exp, dist.description_duplicates_abstract(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_template() -> Result<(), Error> {
+    for name in ["widget", "pg_pair", "semver"] {
+        let meta = template(name);
+        let mut validator = crate::valid::Validator::new();
+        assert_eq!(2, validator.validate(&meta)?, "{name}");
+
+        let dist = Distribution::try_from(meta)?;
+        assert_eq!(name, dist.name());
+        assert_eq!(&Version::new(0, 1, 0), dist.version());
+        let (ext_name, ext) = dist.primary_extension().unwrap();
+        assert_eq!(name, ext_name);
+        assert_eq!(format!("{name}.control"), ext.control().as_str());
+        assert_eq!(format!("sql/{name}.sql"), ext.sql().as_str());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_artifact_version_mismatches() -> Result<(), Error> {
+    // Only the typical-sql corpus artifact is stale.
+    let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus", "v2"]
+        .iter()
+        .collect();
+    for path in Glob::new("*.json")?.walk(dir) {
+        let path = path?.into_path();
+        let dist = Distribution::load(&path)?;
+        let exp = match path.file_name().unwrap().to_str().unwrap() {
+            "typical-sql.json" => {
+                vec!["https://github.com/theory/kv-pair/releases/download/v0.1.7/pair-0.1.7.zip"]
+            }
+            _ => vec![],
+        };
+        assert_eq!(
+            exp,
+            dist.artifact_version_mismatches(),
+            "{}",
+            path.display()
+        );
+    }
+
+    let mut meta = minimal_v2()?;
+    let sha = "0000000000000000000000000000000000000000000000000000000000000000";
+    let artifact = |url: &str, kind: &str| json!({"url": url, "type": kind, "sha256": sha});
+    let base = "https://github.com/theory/kv-pair/releases/download";
+
+    for (name, version, urls, exp) in [
+        ("none", "0.1.8", vec![], vec![]),
+        (
+            "matching source",
+            "0.1.8",
+            vec![format!("{base}/v0.1.8/pair-0.1.8.zip")],
+            vec![],
+        ),
+        (
+            "stale binary",
+            "0.1.8",
+            vec![
+                format!("{base}/v0.1.8/pair-0.1.8.zip"),
+                format!("{base}/v0.1.7/pair-0.1.7-linux-amd64.tar.gz"),
+            ],
+            vec![1],
+        ),
+        (
+            "stale file name",
+            "0.1.8",
+            vec![format!("{base}/v0.1.8/pair-0.1.7.zip")],
+            vec![0],
+        ),
+        (
+            "no version",
+            "0.1.8",
+            vec!["https://example.com/pair.zip".to_string()],
+            vec![],
+        ),
+        (
+            "not a version",
+            "0.1.8",
+            vec![format!("{base}/v0.1.8/pair-0.1.8-x86.64.1.zip")],
+            vec![],
+        ),
+        (
+            "prerelease",
+            "1.0.0-beta1",
+            vec![
+                format!("{base}/v1.0.0-beta1/pair-1.0.0-beta1.zip"),
+                format!("{base}/v1.0.0/pair-1.0.0.zip"),
+            ],
+            vec![1],
+        ),
+    ] {
+        meta["version"] = json!(version);
+        meta["artifacts"] = match urls.is_empty() {
+            true => Value::Null,
+            false => urls.iter().map(|u| artifact(u, "source")).collect(),
+        };
+        let dist: Distribution = serde_json::from_value(meta.clone())?;
+        let exp: Vec<&str> = exp.into_iter().map(|i| urls[i].as_str()).collect();
+        assert_eq!(exp, dist.artifact_version_mismatches(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_prerelease_with_artifacts() -> Result<(), Error> {
+    let meta = minimal_v2()?;
+    let artifacts = json!([{
+        "url": "https://github.com/theory/kv-pair/releases/download/v1.2.1/pair-1.2.1.zip",
+        "type": "source",
+        "sha256": "0000000000000000000000000000000000000000000000000000000000000000",
+    }]);
+
+    for (name, version, artifacts, exp) in [
+        (
+            "prerelease with artifacts",
+            "1.2.1-beta1",
+            Some(&artifacts),
+            true,
+        ),
+        ("release with artifacts", "1.2.1", Some(&artifacts), false),
+        ("prerelease without artifacts", "1.2.1-beta1", None, false),
+        ("release without artifacts", "1.2.1", None, false),
+    ] {
+        let mut meta = meta.clone();
+        meta["version"] = json!(version);
+        if let Some(artifacts) = artifacts {
+            meta["artifacts"] = artifacts.clone();
+        }
+        let dist = Distribution::try_from(meta)?;
+        assert_eq!(exp, dist.prerelease_with_artifacts(), "{name}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_undocumented_items() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+
+    // The minimal extension has no docs.
+    let dist = Distribution::try_from(meta.clone())?;
+    assert_eq!(
+        vec![("extension", "pair".to_string())],
+        dist.undocumented_items()
+    );
+
+    meta["contents"] = json!({
+        "extensions": {
+            "pair": {"control": "pair.control", "sql": "sql/pair.sql", "doc": "doc/pair.md"},
+            "trio": {"control": "trio.control", "sql": "sql/trio.sql"},
+        },
+        "modules": {
+            "hook": {"type": "hook", "lib": "lib/hook", "doc": "doc/hook.md"},
+            "bgw": {"type": "bgw", "lib": "lib/bgw"},
+        },
+        "apps": {
+            "pair_cli": {"bin": "bin/pair_cli", "doc": "doc/pair_cli.md"},
+            "trio_cli": {"bin": "bin/trio_cli"},
+        },
+    });
+    let dist = Distribution::try_from(meta)?;
+    assert_eq!(
+        vec![
+            ("app", "trio_cli".to_string()),
+            ("extension", "trio".to_string()),
+            ("module", "bgw".to_string()),
+        ],
+        dist.undocumented_items()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_docs_by_kind() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+
+    // The minimal extension has no docs.
+    let dist = Distribution::try_from(meta.clone())?;
+    assert_eq!((vec![], vec![], vec![]), dist.docs_by_kind());
+
+    meta["contents"] = json!({
+        "extensions": {
+            "pair": {"control": "pair.control", "sql": "sql/pair.sql", "doc": "doc/pair.md"},
+            "trio": {"control": "trio.control", "sql": "sql/trio.sql"},
+        },
+        "modules": {
+            "bgw": {"type": "bgw", "lib": "lib/bgw"},
+        },
+        "apps": {
+            "pair_cli": {"bin": "bin/pair_cli", "doc": "doc/pair_cli.md"},
+            "trio_cli": {"bin": "bin/trio_cli"},
+        },
+    });
+    let dist = Distribution::try_from(meta)?;
+    let (exts, modules, apps) = dist.docs_by_kind();
+    assert_eq!(vec![("pair", RelativePath::new("doc/pair.md"))], exts);
+    assert!(modules.is_empty());
+    assert_eq!(
+        vec![("pair_cli", RelativePath::new("doc/pair_cli.md"))],
+        apps
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_patches() -> Result<(), Error> {
+    let license = json!({"license": "MIT"});
+    let version = json!({"version": "1.0.0"});
+    Distribution::validate_patches(&[])?;
+    Distribution::validate_patches(&[&license, &version, &json!({})])?;
+
+    for (name, patch) in [
+        ("string", json!("MIT")),
+        ("number", json!(42)),
+        ("bool", json!(true)),
+        ("null", json!(null)),
+        ("array", json!([{"license": "MIT"}])),
+    ] {
+        match Distribution::validate_patches(&[&license, &patch, &version]) {
+            Ok(_) => panic!("{name} unexpectedly passed"),
+            Err(e) => {
+                assert!(matches!(e, Error::PatchNotObject(1)), "{name}: {e}");
+                assert_eq!("patch 1 is not a JSON object", e.to_string(), "{name}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_all_versions() -> Result<(), Error> {
+    let mut meta = crate::tests::v2::valid_v2_distribution();
+    let dist = Distribution::try_from(meta.clone())?;
+    let versions = dist.all_versions();
+    assert!(versions.contains(&("version", "0.26.0".to_string())));
+    assert!(versions.contains(&("postgres", "8.4".to_string())));
+    assert_eq!(
+        vec![
+            ("version", "0.26.0".to_string()),
+            ("postgres", "8.4".to_string()),
+            ("run", "0".to_string()),
+        ],
+        versions,
+    );
+
+    // Test with variations.
+    meta["dependencies"] = json!({
+        "postgres": {"version": ">= 12, < 18"},
+        "packages": {
+            "test": {"requires": {"pkg:pgxn/pgtap": "1.2.0"}},
+            "build": {
+                "requires": {"pkg:generic/python": "3.0"},
+                "recommends": {"pkg:generic/jq": 0},
+            },
+        },
+        "variations": [
+            {
+                "where": {"platforms": ["darwin"]},
+                "dependencies": {
+                    "postgres": {"version": "14"},
+                    "packages": {"build": {"requires": {"pkg:generic/clang": ">= 14.0"}}},
+                },
+            },
+            {
+                "where": {"postgres": {"version": ">= 16"}},
+                "dependencies": {"postgres": {"version": ">= 16", "with": ["zstd"]}},
+            },
+        ],
+    });
+    let dist = Distribution::try_from(meta)?;
+    assert_eq!(
+        vec![
+            ("version", "0.26.0".to_string()),
+            ("postgres", ">= 12, < 18".to_string()),
+            ("postgres", "14".to_string()),
+            ("postgres", ">= 16".to_string()),
+            ("build", "0".to_string()),
+            ("build", ">= 14.0".to_string()),
+            ("build", "3.0".to_string()),
+            ("test", "1.2.0".to_string()),
+        ],
+        dist.all_versions(),
+    );
+
+    // Test without dependencies.
+    let dist = Distribution::try_from(minimal_v2()?)?;
+    assert_eq!(vec![("version", "0.1.8".to_string())], dist.all_versions());
+
+    Ok(())
+}
+
 /// Extracts the subset of val (which must be an instance of Value::Object)
 /// where the property names start with `x_` or `X_`. Used for testing
 /// custom_props.
-fn exes_from(val: &Value) -> HashMap<String, Value> {
+fn exes_from(val: &Value) -> BTreeMap<String, Value> {
     val.as_object()
         .unwrap()
         .into_iter()
@@ -1,47 +1,209 @@
-use super::Distribution;
+//! PGXN v1 `META.json` conversion.
+//!
+//! Functions to convert PGXN [v1] distribution metadata into the [v2]
+//! format.
+//!
+//!   [v1]: https://rfcs.pgxn.org/0001-meta-spec-v1.html
+//!   [v2]: https://github.com/pgxn/rfcs/pull/3
+
+use super::{parse_maintainer_string, Distribution, LIBRARY_SUFFIXES};
 use crate::error::Error;
-use email_address::EmailAddress;
 use serde_json::{json, Map, Value};
-use std::str::FromStr;
 
 /// to_v2 parses v1, which contains PGXN v1 metadata, into a JSON object
 /// containing valid PGXN v2 metadata.
 pub fn to_v2(v1: &Value) -> Result<Value, Error> {
+    to_v2_reported(v1).map(|(v2, _)| v2)
+}
+
+/// A lossy or guessed decision made while converting v1 metadata to v2 by
+/// [`to_v2_reported`].
+#[derive(PartialEq, Debug)]
+pub enum ConversionWarning {
+    /// The control file of the named extension was guessed to be
+    /// `<name>.control` in the distribution root.
+    GuessedControl(String),
+    /// The named extension has no `file`, so its `sql` is set to `UNKNOWN`.
+    UnknownSqlFile(String),
+    /// The named `provides` item was assumed to be an extension rather than
+    /// a module or app.
+    AssumedExtension(String),
+    /// The named `provides` item was assumed to be a module of type
+    /// `extension`, because its `file` is a shared library.
+    AssumedModule(String),
+    /// A v1 license object key was mapped to an SPDX license identifier.
+    LicenseMapped {
+        /// The v1 license object key.
+        from: String,
+        /// The SPDX license identifier it was mapped to.
+        to: String,
+    },
+}
+
+impl std::fmt::Display for ConversionWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversionWarning::GuessedControl(name) => {
+                write!(f, "guessed control file {name}.control for {name}")
+            }
+            ConversionWarning::UnknownSqlFile(name) => write!(f, "unknown sql file for {name}"),
+            ConversionWarning::AssumedExtension(name) => {
+                write!(f, "assumed {name} is an extension")
+            }
+            ConversionWarning::AssumedModule(name) => write!(f, "assumed {name} is a module"),
+            ConversionWarning::LicenseMapped { from, to } => {
+                write!(f, "mapped license {from} to {to}")
+            }
+        }
+    }
+}
+
+/// The audit trail of lossy or guessed decisions and of property mappings
+/// made by [`to_v2_reported`].
+#[derive(PartialEq, Debug, Default)]
+pub struct ConversionReport {
+    warnings: Vec<ConversionWarning>,
+    mappings: Vec<ConversionMapping>,
+}
+
+impl ConversionReport {
+    /// Borrows the conversion warnings, in the order in which they were
+    /// recorded.
+    pub fn warnings(&self) -> &[ConversionWarning] {
+        self.warnings.as_slice()
+    }
+
+    /// Returns true if the conversion recorded no warnings.
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Borrows the mapping of each v1 property to the v2 property it
+    /// became, in the order in which the conversion handled them. See
+    /// [`conversion_map`].
+    pub fn mappings(&self) -> &[ConversionMapping] {
+        self.mappings.as_slice()
+    }
+
+    fn warn(&mut self, warning: ConversionWarning) {
+        self.warnings.push(warning);
+    }
+
+    /// Records that the v1 property at path `from` became the v2 property at
+    /// path `to` with the value `val`.
+    fn map(&mut self, from: &[&str], to: &[&str], val: &Value) {
+        self.mappings
+            .push((from.join("."), to.join("."), val.clone()));
+    }
+
+    /// Records the mapping of each custom property in `v1`, at path `from`,
+    /// to the same key at path `to`.
+    fn map_custom(&mut self, from: &[&str], to: &[&str], v1: &Map<String, Value>) {
+        for (key, val) in v1
+            .iter()
+            .filter(|(key, _)| key.starts_with("x_") || key.starts_with("X_"))
+        {
+            self.map(&[from, &[key]].concat(), &[to, &[key]].concat(), val);
+        }
+    }
+}
+
+/// to_v2_reported parses v1, which contains PGXN v1 metadata, into a JSON
+/// object containing valid PGXN v2 metadata, the same conversion used to
+/// load a v1 [`Distribution`]. It also returns a [`ConversionReport`]
+/// listing the lossy or guessed decisions made during the conversion and
+/// how each v1 property maps to v2.
+pub fn to_v2_reported(v1: &Value) -> Result<(Value, ConversionReport), Error> {
+    let mut report = ConversionReport::default();
+
     // Copy common fields.
-    let mut v2 = v1_to_v2_common(v1);
+    let mut v2 = v1_to_v2_common(v1, &mut report);
 
     // Convert maintainers.
-    v2.insert("maintainers".to_string(), v1_to_v2_maintainers(v1)?);
+    let maintainers = v1_to_v2_maintainers(v1)?;
+    report.map(&["maintainer"], &["maintainers"], &maintainers);
+    v2.insert("maintainers".to_string(), maintainers);
 
     // Convert license.
-    v2.insert("license".to_string(), v1_to_v2_license(v1)?);
+    let license = v1_to_v2_license(v1, &mut report)?;
+    report.map(&["license"], &["license"], &license);
+    v2.insert("license".to_string(), license);
 
     // Convert provides to contents.
-    v2.insert("contents".to_string(), v1_to_v2_contents(v1)?);
+    v2.insert("contents".to_string(), v1_to_v2_contents(v1, &mut report)?);
 
     // Convert tags to classifications.
     if let Some(val) = v1_to_v2_classifications(v1) {
+        report.map(&["tags"], &["classifications", "tags"], &val["tags"]);
         v2.insert("classifications".to_string(), val);
     }
 
     // Convert no_index to ignore.
     if let Some(val) = v1_to_v2_ignore(v1) {
+        for key in ["file", "directory"] {
+            if v1["no_index"].get(key).is_some() {
+                report.map(&["no_index", key], &["ignore"], &val);
+            }
+        }
         v2.insert("ignore".to_string(), val);
     }
 
     // Convert prereqs to dependencies.
-    if let Some(val) = v1_to_v2_dependencies(v1) {
+    if let Some(val) = v1_to_v2_dependencies(v1, &mut report) {
         v2.insert("dependencies".to_string(), val);
     }
 
     // resources
-    if let Some(val) = v1_to_v2_resources(v1) {
+    if let Some(val) = v1_to_v2_resources(v1, &mut report) {
         v2.insert("resources".to_string(), val);
     }
 
-    Ok(Value::Object(v2))
+    Ok((Value::Object(v2), report))
+}
+
+/// A mapping from a v1 property path to the v2 property path it was
+/// converted to, and the resulting v2 value, as returned by
+/// [`conversion_map`].
+pub type ConversionMapping = (String, String, Value);
+
+/// conversion_map converts v1, which contains PGXN v1 metadata, to v2 just
+/// like [`to_v2_reported`], and returns the mapping of each v1 property to
+/// the v2 property it became, along with the v2 value, as recorded in
+/// [`ConversionReport::mappings`]. Paths are the property names from the
+/// root, joined by dots, such as `generated_by` → `producer` or
+/// `provides.pair.file` → `contents.extensions.pair.sql`. Mappings appear in
+/// the order the conversion handles properties, and only for v1 properties
+/// that appear in the v2 output, so of multiple PostgreSQL prereqs only the
+/// one whose version became `dependencies.postgres.version` appears. Use it
+/// to show an author how their v1 `META.json` became v2.
+pub fn conversion_map(v1: &Value) -> Result<Vec<ConversionMapping>, Error> {
+    to_v2_reported(v1).map(|(_, report)| report.mappings)
 }
 
+/// The name of the custom property set by [`to_v2_marked`] to record that
+/// v2 metadata was converted from v1 metadata.
+pub const FROM_V1_PROPERTY: &str = "x_pgxn_from_v1";
+
+/// to_v2_marked parses v1, which contains PGXN v1 metadata, into a JSON
+/// object containing valid PGXN v2 metadata, the same conversion used to
+/// load a v1 [`Distribution`]. It also sets the [`FROM_V1_PROPERTY`] custom
+/// property to `true`, so that the provenance of the v2 metadata survives
+/// serialization. Use
+/// [`Distribution::converted_from_v1`] to check for it.
+pub fn to_v2_marked(v1: &Value) -> Result<Value, Error> {
+    let mut v2 = to_v2(v1)?;
+    if let Value::Object(obj) = &mut v2 {
+        obj.insert(FROM_V1_PROPERTY.to_string(), Value::Bool(true));
+    }
+    Ok(v2)
+}
+
+/// The URL assigned to converted v1 maintainers that have neither an email
+/// address nor a `resources.homepage` to fall back on. Use
+/// [`Maintainer::has_fallback_url`](super::Maintainer::has_fallback_url) to
+/// check for it.
+pub const FALLBACK_MAINTAINER_URL: &str = "https://pgxn.org";
+
 /// from_value parses v1, which contains PGXN v1 metadata, into a
 /// [`Distribution`] object containing valid PGXN v2 metadata.
 pub fn from_value(v1: Value) -> Result<Distribution, Error> {
@@ -49,8 +211,9 @@ pub fn from_value(v1: Value) -> Result<Distribution, Error> {
 }
 
 /// v1_to_v2_common sets up a new v2 map with compatible fields copied from v1
-/// and the `meta-spec` field set appropriately.
-fn v1_to_v2_common(v1: &Value) -> Map<String, Value> {
+/// and the `meta-spec` field set appropriately. Records the mapping of each
+/// copied field in `report`.
+fn v1_to_v2_common(v1: &Value, report: &mut ConversionReport) -> Map<String, Value> {
     let mut v2 = Map::new();
 
     // Copy fields unchanged from v1.
@@ -62,11 +225,15 @@ fn v1_to_v2_common(v1: &Value) -> Map<String, Value> {
         ("generated_by", "producer"),
     ] {
         if let Some(v) = v1.get(k1) {
+            report.map(&[k1], &[k2], v);
             v2.insert(k2.to_string(), v.clone());
         }
     }
 
     // Copy custom properties.
+    if let Some(obj) = v1.as_object() {
+        report.map_custom(&[], &[], obj);
+    }
     v1_value_to_v2_custom_props(v1, &mut v2);
 
     // Set the meta-spec.
@@ -77,6 +244,16 @@ fn v1_to_v2_common(v1: &Value) -> Map<String, Value> {
         json!("https://rfcs.pgxn.org/0003-meta-spec-v2.html"),
     );
     if let Some(v1_spec) = v1.get("meta-spec") {
+        if v1_spec.get("version").is_some() {
+            report.map(
+                &["meta-spec", "version"],
+                &["meta-spec", "version"],
+                &spec["version"],
+            );
+        }
+        if let Some(obj) = v1_spec.as_object() {
+            report.map_custom(&["meta-spec"], &["meta-spec"], obj);
+        }
         v1_value_to_v2_custom_props(v1_spec, &mut spec);
     }
     v2.insert("meta-spec".to_string(), Value::Object(spec));
@@ -125,30 +302,27 @@ fn v1_to_v2_maintainers(v1: &Value) -> Result<Value, Error> {
 /// in for the maintainer `email` and `name` properties, respectively.
 /// Otherwise the string will be saved as the maintainer `name` and the `url`
 /// set to either the `homepage` in the `resources` object in `v1`, or else
-/// `https://pgxn.org`.
+/// [`FALLBACK_MAINTAINER_URL`].
 fn parse_v1_maintainers(v1: &Value, list: &[Value]) -> Result<Value, Error> {
     let mut new_list: Vec<Value> = Vec::with_capacity(list.len());
     for v in list {
         if let Some(str) = v.as_str() {
-            if let Ok(email) = EmailAddress::from_str(str) {
+            let maintainer = parse_maintainer_string(str);
+            if let Some(email) = maintainer.email {
                 new_list.push(json!({
-                    "name": match email.display_part() {
-                        "" => str,
-                        d => d,
-                    },
-                    "email": email.email(),
+                    "name": maintainer.name,
+                    "email": email,
                 }));
             } else {
                 // No email address found. Try using resources.homepage.
-                const FALLBACK_URL: &str = "https://pgxn.org";
                 let url = match v1.get("resources") {
                     Some(Value::Object(resources)) => match resources.get("homepage") {
                         Some(Value::String(home)) => home.to_string(),
-                        _ => FALLBACK_URL.to_string(),
+                        _ => FALLBACK_MAINTAINER_URL.to_string(),
                     },
-                    _ => FALLBACK_URL.to_string(),
+                    _ => FALLBACK_MAINTAINER_URL.to_string(),
                 };
-                new_list.push(json!({"name": str, "url": url}));
+                new_list.push(json!({"name": maintainer.name, "url": url}));
             }
         } else {
             return Err(Error::Invalid("maintainer", 1, v.clone()));
@@ -172,8 +346,10 @@ fn parse_v1_maintainers(v1: &Value, list: &[Value]) -> Result<Value, Error> {
 ///     SPDX license based on its key. The list of supported keys is derived
 ///     from those used on PGXN, and all should be mapped to valid values. If
 ///     not, an error will be returned. Otherwise, the resulting list of
-///     license strings is `OR`ed into a license expression.
-fn v1_to_v2_license(v1: &Value) -> Result<Value, Error> {
+///     license strings is `OR`ed into a license expression. Records a
+///     [`ConversionWarning::LicenseMapped`] in `report` for each key that
+///     differs from its SPDX license.
+fn v1_to_v2_license(v1: &Value, report: &mut ConversionReport) -> Result<Value, Error> {
     if let Some(license) = v1.get("license") {
         return match license {
             Value::String(l) => {
@@ -207,21 +383,28 @@ fn v1_to_v2_license(v1: &Value) -> Result<Value, Error> {
                     // database:
                     //
                     // `SELECT DISTINCT jsonb(meta)->>'license' FROM distributions;`
-                    match (k.as_str(), v.as_str()) {
-                        ("PostgreSQL", _) => list.push(k.to_string()),
-                        ("Apache", _) => list.push("Apache-2.0".to_string()),
-                        ("ISC", _) => list.push(k.to_string()),
-                        ("mit", _) => list.push("MIT".to_string()),
-                        ("mozilla_2_0", _) => list.push("MPL-2.0".to_string()),
-                        ("gpl_3", _) => list.push("GPL-3.0-only".to_string()),
-                        ("BSD", _) => list.push("BSD-2-Clause".to_string()),
-                        ("BSD 2 Clause", _) => list.push("BSD-2-Clause".to_string()),
+                    let spdx = match (k.as_str(), v.as_str()) {
+                        ("PostgreSQL", _) => "PostgreSQL",
+                        ("Apache", _) => "Apache-2.0",
+                        ("ISC", _) => "ISC",
+                        ("mit", _) => "MIT",
+                        ("mozilla_2_0", _) => "MPL-2.0",
+                        ("gpl_3", _) => "GPL-3.0-only",
+                        ("BSD", _) => "BSD-2-Clause",
+                        ("BSD 2 Clause", _) => "BSD-2-Clause",
                         (
                             "restricted",
                             Some("https://github.com/diffix/pg_diffix/blob/master/LICENSE.md"),
-                        ) => list.push("BUSL-1.1".to_string()),
+                        ) => "BUSL-1.1",
                         _ => return Err(Error::Invalid("license", 1, v.clone())),
+                    };
+                    if k != spdx {
+                        report.warn(ConversionWarning::LicenseMapped {
+                            from: k.to_string(),
+                            to: spdx.to_string(),
+                        });
                     }
+                    list.push(spdx.to_string());
                 }
                 return Ok(Value::String(list.join(" OR ")));
             }
@@ -275,28 +458,54 @@ fn license_expression_for(name: &str) -> Option<&str> {
 /// apps, in which case the result here will be incorrect, though valid v2
 /// metadata.
 ///
+/// The exception is a `provides` item whose `file` is a shared library, with
+/// a name ending in `.so`, `.dll`, or `.dylib`, which most likely provided a
+/// pure C module. Such items become `modules` of type `extension`, with the
+/// `lib` property set to `file` without its suffix. Items with no `file`
+/// remain extensions, since there is no library path to guess.
+///
 /// Returns the resulting object in a valid `contents` object with
-/// `extensions` as the sole property.
-fn v1_to_v2_contents(v1: &Value) -> Result<Value, Error> {
+/// `extensions`, `modules`, or both. Records each of these assumptions as a
+/// [`ConversionWarning`] in `report`, along with the mapping of each
+/// `provides` item and its properties.
+fn v1_to_v2_contents(v1: &Value, report: &mut ConversionReport) -> Result<Value, Error> {
     if let Some(provides) = v1.get("provides") {
         // Assume everything is an extension. It's not true, but most common.
         let mut extensions = Map::new();
+        let mut modules = Map::new();
         if let Value::Object(obj) = provides {
             for (ext, spec) in obj {
                 match spec {
                     Value::Object(obj) => {
                         let mut v2_spec = Map::new();
-                        // Assume control file is in the distribution root.
-                        v2_spec.insert(
-                            "control".to_string(),
-                            Value::String(ext.to_string() + ".control"),
-                        );
-
-                        // Assume file points to an SQL file (it usually does).
-                        if obj.contains_key("file") {
-                            v2_spec.insert("sql".to_string(), obj["file"].clone());
+                        if let Some(lib) = obj
+                            .get("file")
+                            .and_then(Value::as_str)
+                            .and_then(library_path)
+                        {
+                            // Shared libraries are modules, not extensions.
+                            report.warn(ConversionWarning::AssumedModule(ext.to_string()));
+                            v2_spec.insert("type".to_string(), json!("extension"));
+                            v2_spec.insert("lib".to_string(), json!(lib));
                         } else {
-                            v2_spec.insert("sql".to_string(), Value::String("UNKNOWN".to_string()));
+                            report.warn(ConversionWarning::AssumedExtension(ext.to_string()));
+                            // Assume control file is in the distribution root.
+                            report.warn(ConversionWarning::GuessedControl(ext.to_string()));
+                            v2_spec.insert(
+                                "control".to_string(),
+                                Value::String(ext.to_string() + ".control"),
+                            );
+
+                            // Assume file points to an SQL file (it usually does).
+                            if obj.contains_key("file") {
+                                v2_spec.insert("sql".to_string(), obj["file"].clone());
+                            } else {
+                                report.warn(ConversionWarning::UnknownSqlFile(ext.to_string()));
+                                v2_spec.insert(
+                                    "sql".to_string(),
+                                    Value::String("UNKNOWN".to_string()),
+                                );
+                            }
                         }
 
                         // Clone directly compatible properties.
@@ -309,7 +518,31 @@ fn v1_to_v2_contents(v1: &Value) -> Result<Value, Error> {
                         // Copy extension custom properties.
                         v1_to_v2_custom_props(obj, &mut v2_spec);
 
-                        extensions.insert(ext.to_string(), Value::Object(v2_spec));
+                        // Record the mappings.
+                        let (kind, file) = match v2_spec.contains_key("lib") {
+                            true => ("modules", "lib"),
+                            false => ("extensions", "sql"),
+                        };
+                        let (from, to) = (["provides", ext], ["contents", kind, ext]);
+                        report.map(&from, &to, &Value::Object(v2_spec.clone()));
+                        for (k1, k2) in
+                            [("file", file), ("docfile", "doc"), ("abstract", "abstract")]
+                        {
+                            if let (Some(_), Some(val)) = (obj.get(k1), v2_spec.get(k2)) {
+                                report.map(
+                                    &[&from[..], &[k1]].concat(),
+                                    &[&to[..], &[k2]].concat(),
+                                    val,
+                                );
+                            }
+                        }
+                        report.map_custom(&from, &to, obj);
+
+                        if kind == "modules" {
+                            modules.insert(ext.to_string(), Value::Object(v2_spec));
+                        } else {
+                            extensions.insert(ext.to_string(), Value::Object(v2_spec));
+                        }
                     }
                     _ => return Err(Error::Invalid("extension", 1, spec.clone())),
                 }
@@ -318,11 +551,27 @@ fn v1_to_v2_contents(v1: &Value) -> Result<Value, Error> {
             return Err(Error::Invalid("provides", 1, provides.clone()));
         }
 
-        return Ok(json!({"extensions": extensions}));
+        let mut contents = Map::new();
+        if !extensions.is_empty() || modules.is_empty() {
+            contents.insert("extensions".to_string(), Value::Object(extensions));
+        }
+        if !modules.is_empty() {
+            contents.insert("modules".to_string(), Value::Object(modules));
+        }
+        return Ok(Value::Object(contents));
     }
     Err(Error::Missing("provides"))
 }
 
+/// library_path returns `file` without its suffix if it names a shared
+/// library, ending in `.so`, `.dll`, or `.dylib`. Returns `None` otherwise.
+fn library_path(file: &str) -> Option<&str> {
+    LIBRARY_SUFFIXES
+        .into_iter()
+        .find_map(|suffix| file.strip_suffix(suffix))
+        .filter(|lib| !lib.is_empty() && !lib.ends_with('/'))
+}
+
 /// v1_to_v2_classifications clones the tags array in v1 into an object with
 /// `tags` as the key. Returns None if v2 has no `tags` key.
 fn v1_to_v2_classifications(v1: &Value) -> Option<Value> {
@@ -363,8 +612,10 @@ fn v1_to_v2_ignore(v1: &Value) -> Option<Value> {
 /// `pkg:postgres` purls and all others are specified using `pkg:pgxn` purls.
 /// The exception is PostgreSQL dependencies, which are specified under the v1
 /// `postgres` key with the lowest version found. v2 does not currently
-/// support suggesting or recommending higher versions.
-fn v1_to_v2_dependencies(v1: &Value) -> Option<Value> {
+/// support suggesting or recommending higher versions. Records the mapping
+/// of each dependency in `report`, including only the PostgreSQL
+/// dependency whose version was kept.
+fn v1_to_v2_dependencies(v1: &Value, report: &mut ConversionReport) -> Option<Value> {
     use semver::Version;
     match v1.get("prereqs") {
         Some(Value::Object(prereqs)) => {
@@ -372,6 +623,7 @@ fn v1_to_v2_dependencies(v1: &Value) -> Option<Value> {
             // compare against unlikely v9999.
             let max_version = Version::parse("9999.0.0").unwrap();
             let mut pg_version = max_version.clone();
+            let mut pg_from = None;
 
             // Iterate over the v2 phases mapped to the v2 phases.
             let mut dependencies = Map::new();
@@ -400,16 +652,20 @@ fn v1_to_v2_dependencies(v1: &Value) -> Option<Value> {
                                         if let Ok(pgv) = Version::parse(version) {
                                             if pgv < pg_version {
                                                 pg_version = pgv;
+                                                pg_from = Some([phase1, rel_name, name]);
                                             }
                                         }
                                     }
                                 } else {
                                     // Insert a purl into the deps object.
                                     // Does ext need to be URI-path encoded?
-                                    deps.insert(
-                                        format!("pkg:{}/{ext}", source_for(&ext)),
-                                        version.clone(),
+                                    let purl = format!("pkg:{}/{ext}", source_for(&ext));
+                                    report.map(
+                                        &["prereqs", phase1, rel_name, name],
+                                        &["dependencies", "packages", phase2, rel_name, &purl],
+                                        version,
                                     );
+                                    deps.insert(purl, version.clone());
                                 }
                             }
 
@@ -420,6 +676,11 @@ fn v1_to_v2_dependencies(v1: &Value) -> Option<Value> {
                     }
 
                     // Copy phase custom properties to the phase.
+                    report.map_custom(
+                        &["prereqs", phase1],
+                        &["dependencies", "packages", phase2],
+                        relation,
+                    );
                     v1_to_v2_custom_props(relation, &mut phase);
 
                     if !phase.is_empty() {
@@ -429,11 +690,18 @@ fn v1_to_v2_dependencies(v1: &Value) -> Option<Value> {
             }
 
             // Set the Postgres version if we have one.
-            if pg_version < max_version {
-                dependencies.insert("postgres".to_string(), json!({"version": pg_version}));
+            if let Some([phase, rel, name]) = pg_from {
+                let postgres = json!({"version": pg_version});
+                report.map(
+                    &["prereqs", phase, rel, name],
+                    &["dependencies", "postgres", "version"],
+                    &postgres["version"],
+                );
+                dependencies.insert("postgres".to_string(), postgres);
             }
 
             // Copy prereqs custom properties to the packages.
+            report.map_custom(&["prereqs"], &["dependencies", "packages"], prereqs);
             v1_to_v2_custom_props(prereqs, &mut packages);
 
             // If we have extensions, add them.
@@ -456,82 +724,92 @@ fn v1_to_v2_dependencies(v1: &Value) -> Option<Value> {
 /// Return "postgres" if ext is a Postgres core extension or PL and "pgxn" for
 /// all other values.
 fn source_for(ext: &str) -> String {
-    match ext {
-        "adminpack"
-        | "amcheck"
-        | "auth_delay"
-        | "auto_explain"
-        | "basebackup_to_shell"
-        | "basic_archive"
-        | "bloom"
-        | "bool_plperl"
-        | "btree_gin"
-        | "btree_gist"
-        | "chkpass"
-        | "citext"
-        | "cube"
-        | "dblink"
-        | "dict_int"
-        | "dict_xsyn"
-        | "earthdistance"
-        | "file_fdw"
-        | "fuzzystrmatch"
-        | "hstore"
-        | "hstore_plperl"
-        | "hstore_plpython"
-        | "intagg"
-        | "intarray"
-        | "isn"
-        | "jsonb_plperl"
-        | "jsonb_plpython"
-        | "lo"
-        | "ltree"
-        | "ltree_plpython"
-        | "oid2name"
-        | "old_snapshot"
-        | "pageinspect"
-        | "passwordcheck"
-        | "pg_buffercache"
-        | "pg_freespacemap"
-        | "pg_prewarm"
-        | "pg_standby"
-        | "pg_stat_statements"
-        | "pg_surgery"
-        | "pg_trgm"
-        | "pg_visibility"
-        | "pg_walinspect"
-        | "pgcrypto"
-        | "pgrowlocks"
-        | "pgstattuple"
-        | "plperl"
-        | "plperlu"
-        | "plpgsql"
-        | "plpython"
-        | "plpythonu"
-        | "plpython2u"
-        | "plpython3u"
-        | "pltcl"
-        | "pltclu"
-        | "postgres_fdw"
-        | "seg"
-        | "sepgsql"
-        | "spi"
-        | "sslinfo"
-        | "start-scripts"
-        | "tablefunc"
-        | "tcn"
-        | "test_decoding"
-        | "tsearch2"
-        | "tsm_system_rows"
-        | "tsm_system_time"
-        | "unaccent"
-        | "uuid-ossp"
-        | "vacuumlo"
-        | "xml2" => "postgres".to_string(),
-        _ => "pgxn".to_string(),
+    if is_core_extension(ext) {
+        "postgres".to_string()
+    } else {
+        "pgxn".to_string()
     }
 }
 
+/// Returns true if `ext`, which must be lowercase, is a Postgres core
+/// extension or PL.
+pub fn is_core_extension(ext: &str) -> bool {
+    matches!(
+        ext,
+        "adminpack"
+            | "amcheck"
+            | "auth_delay"
+            | "auto_explain"
+            | "basebackup_to_shell"
+            | "basic_archive"
+            | "bloom"
+            | "bool_plperl"
+            | "btree_gin"
+            | "btree_gist"
+            | "chkpass"
+            | "citext"
+            | "cube"
+            | "dblink"
+            | "dict_int"
+            | "dict_xsyn"
+            | "earthdistance"
+            | "file_fdw"
+            | "fuzzystrmatch"
+            | "hstore"
+            | "hstore_plperl"
+            | "hstore_plpython"
+            | "intagg"
+            | "intarray"
+            | "isn"
+            | "jsonb_plperl"
+            | "jsonb_plpython"
+            | "lo"
+            | "ltree"
+            | "ltree_plpython"
+            | "oid2name"
+            | "old_snapshot"
+            | "pageinspect"
+            | "passwordcheck"
+            | "pg_buffercache"
+            | "pg_freespacemap"
+            | "pg_prewarm"
+            | "pg_standby"
+            | "pg_stat_statements"
+            | "pg_surgery"
+            | "pg_trgm"
+            | "pg_visibility"
+            | "pg_walinspect"
+            | "pgcrypto"
+            | "pgrowlocks"
+            | "pgstattuple"
+            | "plperl"
+            | "plperlu"
+            | "plpgsql"
+            | "plpython"
+            | "plpythonu"
+            | "plpython2u"
+            | "plpython3u"
+            | "pltcl"
+            | "pltclu"
+            | "postgres_fdw"
+            | "seg"
+            | "sepgsql"
+            | "spi"
+            | "sslinfo"
+            | "start-scripts"
+            | "tablefunc"
+            | "tcn"
+            | "test_decoding"
+            | "tsearch2"
+            | "tsm_system_rows"
+            | "tsm_system_time"
+            | "unaccent"
+            | "uuid-ossp"
+            | "vacuumlo"
+            | "xml2"
+    )
+}
+
 /// v1_to_v2_resources copies v1 resources values to compatible v2 resources
 /// values:
 ///
@@ -539,8 +817,9 @@ fn source_for(ext: &str) -> String {
 /// *   v1 `bugtracker.web` or `bugtracker.mailto` are copied to v2 `issues`
 /// *   v1 `repository.web` or `repository.url` are copied to v2 `repository`
 ///
-/// Returns `None` if v1 has no compatible resources.
-fn v1_to_v2_resources(v1: &Value) -> Option<Value> {
+/// Returns `None` if v1 has no compatible resources. Records the mapping of
+/// each copied value in `report`.
+fn v1_to_v2_resources(v1: &Value, report: &mut ConversionReport) -> Option<Value> {
     match v1.get("resources") {
         Some(Value::Object(resources)) => {
             let mut ret = Map::new();
@@ -567,6 +846,18 @@ fn v1_to_v2_resources(v1: &Value) -> Option<Value> {
                 }
             }
 
+            // Record the mappings.
+            for (k1, k2) in [
+                ("homepage", "homepage"),
+                ("bugtracker", "issues"),
+                ("repository", "repository"),
+            ] {
+                if let Some(val) = ret.get(k2) {
+                    report.map(&["resources", k1], &["resources", k2], val);
+                }
+            }
+            report.map_custom(&["resources"], &["resources"], resources);
+
             // Copy any custom fields.
             v1_to_v2_custom_props(resources, &mut ret);
 
@@ -45,7 +45,7 @@ fn test_v1_v2_common() {
             json!({"name": "pair", "version": "1.2.3", "meta-spec": meta}),
         ),
     ] {
-        let v2 = v1_to_v2_common(&input);
+        let v2 = v1_to_v2_common(&input, &mut ConversionReport::default());
         assert_eq!(expect, Value::Object(v2), "{name}");
     }
 }
@@ -236,7 +236,7 @@ fn test_v1_v2_licenses() {
             ),
         ),
     ] {
-        match v1_to_v2_license(&input) {
+        match v1_to_v2_license(&input, &mut ConversionReport::default()) {
             Ok(lic) => assert_eq!(expect, lic, "{name}"),
             Err(e) => panic!("{name}: {e}"),
         }
@@ -278,7 +278,7 @@ fn test_v1_v2_licenses_error() {
         ),
         ("nonexistent", json!({}), "license property missing"),
     ] {
-        match v1_to_v2_license(&input) {
+        match v1_to_v2_license(&input, &mut ConversionReport::default()) {
             Ok(_) => panic!("{name} unexpectedly succeeded"),
             Err(e) => assert_eq!(err, e.to_string(), "{name}"),
         }
@@ -327,13 +327,107 @@ fn test_v1_v2_contents() {
     ] {
         let input = json!({"provides": input});
         let expect = json!({"extensions": expect});
-        match v1_to_v2_contents(&input) {
+        match v1_to_v2_contents(&input, &mut ConversionReport::default()) {
             Ok(ext) => assert_eq!(expect, ext, "{name}"),
             Err(e) => panic!("{name}: {e}"),
         }
     }
 }
 
+#[test]
+fn test_v1_v2_contents_modules() -> Result<(), Error> {
+    use ConversionWarning::*;
+    for (name, input, expect, warnings) in [
+        (
+            "sql",
+            json!({"widget": {"file": "sql/widget.sql"}}),
+            json!({"extensions": {"widget": {
+                "control": "widget.control",
+                "sql": "sql/widget.sql",
+            }}}),
+            vec![
+                AssumedExtension("widget".to_string()),
+                GuessedControl("widget".to_string()),
+            ],
+        ),
+        (
+            "so",
+            json!({"widget": {"file": "src/widget.so", "docfile": "doc/widget.md", "x_y": 1}}),
+            json!({"modules": {"widget": {
+                "type": "extension",
+                "lib": "src/widget",
+                "doc": "doc/widget.md",
+                "x_y": 1,
+            }}}),
+            vec![AssumedModule("widget".to_string())],
+        ),
+        (
+            "dll",
+            json!({"widget": {"file": "widget.dll"}}),
+            json!({"modules": {"widget": {"type": "extension", "lib": "widget"}}}),
+            vec![AssumedModule("widget".to_string())],
+        ),
+        (
+            "mixed",
+            json!({
+                "pair": {"file": "sql/pair.sql"},
+                "widget": {"file": "lib/widget.dylib", "abstract": "Widgets"},
+            }),
+            json!({
+                "extensions": {"pair": {"control": "pair.control", "sql": "sql/pair.sql"}},
+                "modules": {"widget": {"type": "extension", "lib": "lib/widget", "abstract": "Widgets"}},
+            }),
+            vec![
+                AssumedExtension("pair".to_string()),
+                GuessedControl("pair".to_string()),
+                AssumedModule("widget".to_string()),
+            ],
+        ),
+        (
+            "bare suffix",
+            json!({"widget": {"file": ".so"}}),
+            json!({"extensions": {"widget": {"control": "widget.control", "sql": ".so"}}}),
+            vec![
+                AssumedExtension("widget".to_string()),
+                GuessedControl("widget".to_string()),
+            ],
+        ),
+    ] {
+        let input = json!({"provides": input});
+        let mut report = ConversionReport::default();
+        assert_eq!(expect, v1_to_v2_contents(&input, &mut report)?, "{name}");
+        assert_eq!(warnings, report.warnings(), "{name}");
+    }
+
+    assert_eq!(
+        "assumed widget is a module",
+        AssumedModule("widget".to_string()).to_string()
+    );
+
+    // Make sure the whole conversion is valid.
+    let v1 = json!({
+        "name": "widget",
+        "abstract": "Widgets and sprockets",
+        "version": "0.2.5",
+        "maintainer": "David E. Wheeler <theory@pgxn.org>",
+        "license": "postgresql",
+        "provides": {"widget": {"file": "src/widget.so", "version": "0.2.5"}},
+        "meta-spec": {"version": "1.0.0"},
+    });
+    let dist = from_value(v1.clone())?;
+    let modules = dist.contents().modules().unwrap();
+    assert_eq!("src/widget", modules["widget"].lib());
+    assert!(dist.contents().extensions().is_none());
+    let map = conversion_map(&v1)?;
+    assert!(map.contains(&(
+        "provides.widget.file".to_string(),
+        "contents.modules.widget.lib".to_string(),
+        json!("src/widget"),
+    )));
+
+    Ok(())
+}
+
 #[test]
 fn test_v1_v2_contents_err() {
     for (name, input, err) in [
@@ -349,7 +443,7 @@ fn test_v1_v2_contents_err() {
             "invalid v1 extension value: []",
         ),
     ] {
-        match v1_to_v2_contents(&input) {
+        match v1_to_v2_contents(&input, &mut ConversionReport::default()) {
             Ok(_) => panic!("{name} unexpectedly succeeded"),
             Err(e) => assert_eq!(err, e.to_string(), "{name}"),
         }
@@ -512,7 +606,11 @@ fn test_v1_v2_dependencies() {
         ("number prereqs", json!({"prereqs": 42}), None),
         ("string prereqs", json!({"prereqs": "hi"}), None),
     ] {
-        assert_eq!(expect, v1_to_v2_dependencies(&input), "{name}")
+        assert_eq!(
+            expect,
+            v1_to_v2_dependencies(&input, &mut ConversionReport::default()),
+            "{name}"
+        )
     }
 }
 
@@ -681,10 +779,225 @@ fn test_v1_v2_resources() {
             })),
         ),
     ] {
-        assert_eq!(expect, v1_to_v2_resources(&input), "{name}")
+        assert_eq!(
+            expect,
+            v1_to_v2_resources(&input, &mut ConversionReport::default()),
+            "{name}"
+        )
     }
 }
 
+#[test]
+fn test_to_v2_reported() -> Result<(), Error> {
+    use ConversionWarning::*;
+    let base = json!({
+        "name": "widget",
+        "abstract": "Widgets and sprockets",
+        "version": "0.2.5",
+        "maintainer": "David E. Wheeler <theory@pgxn.org>",
+        "license": "postgresql",
+        "provides": {"widget": {"file": "sql/widget.sql.in", "version": "0.2.5"}},
+        "meta-spec": {"version": "1.0.0"},
+    });
+
+    for (name, patch, exp) in [
+        (
+            "provides",
+            json!({}),
+            vec![
+                AssumedExtension("widget".to_string()),
+                GuessedControl("widget".to_string()),
+            ],
+        ),
+        (
+            "no file",
+            json!({"provides": {"widget": {"file": null}}}),
+            vec![
+                AssumedExtension("widget".to_string()),
+                GuessedControl("widget".to_string()),
+                UnknownSqlFile("widget".to_string()),
+            ],
+        ),
+        (
+            "license object",
+            json!({"license": {
+                "PostgreSQL": "https://www.postgresql.org/about/licence",
+                "Apache": "http://www.apache.org/licenses/LICENSE-2.0",
+            }}),
+            vec![
+                LicenseMapped {
+                    from: "Apache".to_string(),
+                    to: "Apache-2.0".to_string(),
+                },
+                AssumedExtension("widget".to_string()),
+                GuessedControl("widget".to_string()),
+            ],
+        ),
+    ] {
+        let mut v1 = base.clone();
+        json_patch::merge(&mut v1, &patch);
+        let (v2, report) = to_v2_reported(&v1)?;
+        assert_eq!(to_v2(&v1)?, v2, "{name}");
+        assert_eq!(exp, report.warnings(), "{name}");
+        assert!(!report.is_empty(), "{name}");
+    }
+
+    assert_eq!(
+        "mapped license Apache to Apache-2.0",
+        LicenseMapped {
+            from: "Apache".to_string(),
+            to: "Apache-2.0".to_string()
+        }
+        .to_string(),
+    );
+    assert_eq!(
+        "unknown sql file for widget",
+        UnknownSqlFile("widget".to_string()).to_string()
+    );
+
+    // Errors pass through.
+    match to_v2_reported(&json!({"maintainer": "theory"})) {
+        Ok(_) => panic!("missing license unexpectedly succeeded"),
+        Err(e) => assert_eq!("license property missing", e.to_string()),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_conversion_map() -> Result<(), Error> {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus", "v1", "widget.json"]
+        .iter()
+        .collect();
+    let v1: Value = serde_json::from_reader(File::open(path)?)?;
+    let map = conversion_map(&v1)?;
+    let v2 = to_v2(&v1)?;
+
+    // Every v2 value should be at its v2 path.
+    for (from, to, val) in &map {
+        let pointer: String = to
+            .split('.')
+            .map(|k| format!("/{}", k.replace('/', "~1")))
+            .collect();
+        assert_eq!(Some(val), v2.pointer(&pointer), "{from} -> {to}");
+    }
+
+    let pairs: Vec<(&str, &str)> = map
+        .iter()
+        .map(|(f, t, _)| (f.as_str(), t.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("name", "name"),
+            ("abstract", "abstract"),
+            ("description", "description"),
+            ("version", "version"),
+            ("generated_by", "producer"),
+            ("meta-spec.version", "meta-spec.version"),
+            ("maintainer", "maintainers"),
+            ("license", "license"),
+            ("provides.widget", "contents.extensions.widget"),
+            ("provides.widget.file", "contents.extensions.widget.sql"),
+            ("tags", "classifications.tags"),
+            ("no_index.file", "ignore"),
+            ("no_index.directory", "ignore"),
+            (
+                "prereqs.runtime.requires.plpgsql",
+                "dependencies.packages.run.requires.pkg:postgres/plpgsql"
+            ),
+            (
+                "prereqs.runtime.requires.PostgreSQL",
+                "dependencies.postgres.version"
+            ),
+            ("resources.homepage", "resources.homepage"),
+        ],
+        pairs,
+    );
+
+    // Check the maintainer and license values.
+    let mapped = |from: &str| map.iter().find(|(f, _, _)| f == from).map(|(_, _, v)| v);
+    assert_eq!(
+        Some(&json!([{"name": "David E. Wheeler", "email": "theory@pgxn.org"}])),
+        mapped("maintainer"),
+    );
+    assert_eq!(Some(&json!("PostgreSQL")), mapped("license"));
+    assert_eq!(
+        Some(&json!("8.0.0")),
+        mapped("prereqs.runtime.requires.PostgreSQL")
+    );
+
+    // Only the PostgreSQL prereq whose version was kept is mapped.
+    let mut v1 = v1;
+    v1["prereqs"]["runtime"]["requires"]["PostgreSQL"] = json!("9.0.0");
+    let (_, report) = to_v2_reported(&v1)?;
+    let pg: Vec<_> = report
+        .mappings()
+        .iter()
+        .filter(|(_, to, _)| to == "dependencies.postgres.version")
+        .collect();
+    assert_eq!(
+        vec![&(
+            "prereqs.runtime.recommends.PostgreSQL".to_string(),
+            "dependencies.postgres.version".to_string(),
+            json!("8.4.0"),
+        )],
+        pg,
+    );
+
+    // Custom properties and resources.
+    let v1 = json!({
+        "name": "widget",
+        "version": "0.2.5",
+        "maintainer": "David E. Wheeler <theory@pgxn.org>",
+        "license": "postgresql",
+        "provides": {"widget": {"file": "widget.sql", "docfile": "widget.md", "x_y": 1}},
+        "resources": {
+            "bugtracker": {"mailto": "bugs@example.com"},
+            "repository": {"web": "https://example.com/widget"},
+            "x_z": true,
+        },
+        "meta-spec": {"version": "1.0.0", "x_a": "hi"},
+        "x_b": [1],
+    });
+    let map = conversion_map(&v1)?;
+    for (from, to, val) in [
+        ("x_b", "x_b", json!([1])),
+        ("meta-spec.x_a", "meta-spec.x_a", json!("hi")),
+        (
+            "provides.widget.docfile",
+            "contents.extensions.widget.doc",
+            json!("widget.md"),
+        ),
+        (
+            "provides.widget.x_y",
+            "contents.extensions.widget.x_y",
+            json!(1),
+        ),
+        (
+            "resources.bugtracker",
+            "resources.issues",
+            json!("mailto:bugs@example.com"),
+        ),
+        (
+            "resources.repository",
+            "resources.repository",
+            json!("https://example.com/widget"),
+        ),
+        ("resources.x_z", "resources.x_z", json!(true)),
+    ] {
+        let exp = (from.to_string(), to.to_string(), val);
+        assert!(map.contains(&exp), "missing {exp:?} in {map:?}");
+    }
+
+    // Errors pass through.
+    match conversion_map(&json!({"maintainer": "theory"})) {
+        Ok(_) => panic!("missing license unexpectedly succeeded"),
+        Err(e) => assert_eq!("license property missing", e.to_string()),
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_from_value() -> Result<(), Error> {
     use wax::Glob;
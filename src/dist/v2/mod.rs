@@ -1,7 +1,35 @@
-use super::Distribution;
+use super::{App, Distribution, Extension, Module};
 use crate::error::Error;
+use serde::Deserialize;
 use serde_json::Value;
 
 pub fn from_value(meta: Value) -> Result<Distribution, Error> {
-    Ok(serde_json::from_value(meta)?)
+    Distribution::deserialize(&meta).map_err(|e| invalid_contents_item(&meta).unwrap_or(e.into()))
+}
+
+/// Walks the `contents` of `meta` to find the first extension, module, or app
+/// that cannot be deserialized, and returns an [`Error::Invalid`] identifying
+/// it by name. Returns `None` if all the contents items are valid. Used to
+/// give context to serde errors, which do not say which item failed.
+pub(crate) fn invalid_contents_item(meta: &Value) -> Option<Error> {
+    let contents = meta.get("contents")?;
+    invalid_item::<Extension>(contents, "extensions", "extension")
+        .or_else(|| invalid_item::<Module>(contents, "modules", "module"))
+        .or_else(|| invalid_item::<App>(contents, "apps", "app"))
+}
+
+/// Returns an [`Error::Invalid`] naming the first item in the `key` object of
+/// `contents` that cannot be deserialized into a `T`.
+fn invalid_item<'a, T: Deserialize<'a>>(
+    contents: &'a Value,
+    key: &str,
+    label: &'static str,
+) -> Option<Error> {
+    let Some(Value::Object(items)) = contents.get(key) else {
+        return None;
+    };
+    items
+        .iter()
+        .find(|(_, item)| T::deserialize(*item).is_err())
+        .map(|(name, _)| Error::Invalid(label, 2, Value::String(name.clone())))
 }
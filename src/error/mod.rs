@@ -14,6 +14,10 @@ pub enum Error {
     #[error("cannot determine meta-spec version")]
     UnknownSpec,
 
+    /// Metadata declares a meta spec version later than this crate supports.
+    #[error("unsupported meta-spec version {0}")]
+    UnsupportedSpec(u8),
+
     /// A schema file has no `$id` property.
     #[error("no $id found in schema")]
     UnknownSchemaId,
@@ -32,6 +36,16 @@ pub enum Error {
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
 
+    /// Serde YAML error.
+    #[cfg(feature = "yaml")]
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+
+    /// TOML serialization error.
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    Toml(#[from] toml::ser::Error),
+
     /// IO error.
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -55,6 +69,18 @@ pub enum Error {
     /// Hash digest mismatch.
     #[error("{0} digest {1} does not match {2}")]
     Digest(&'static str, String, String),
+
+    /// Duplicate JSON object key.
+    #[error("duplicate key \"{0}\"")]
+    DuplicateKey(String),
+
+    /// Merge patch that is not a JSON object.
+    #[error("patch {0} is not a JSON object")]
+    PatchNotObject(usize),
+
+    /// Release date in the future.
+    #[error("release date {0} is in the future")]
+    FutureDate(chrono::DateTime<chrono::Utc>),
 }
 
 impl<'s, 'v> From<boon::ValidationError<'s, 'v>> for Error {
@@ -20,6 +20,14 @@ fn unknown_spec() {
     )
 }
 
+#[test]
+fn unsupported_spec() {
+    assert_eq!(
+        Error::UnsupportedSpec(3).to_string(),
+        "unsupported meta-spec version 3"
+    )
+}
+
 #[test]
 fn unknown_schema_id() {
     assert_eq!(Error::UnknownSchemaId.to_string(), "no $id found in schema")
@@ -60,6 +68,26 @@ fn serde() {
     assert_eq!(exp, err.to_string());
 }
 
+#[test]
+#[cfg(feature = "yaml")]
+fn yaml() {
+    let yaml_err = serde_yaml::from_str::<String>("[]").unwrap_err();
+    let exp = yaml_err.to_string();
+    let err: Error = yaml_err.into();
+    assert!(matches!(err, Error::Yaml { .. }));
+    assert_eq!(exp, err.to_string());
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn toml() {
+    let toml_err = toml::to_string(&Some(())).unwrap_err();
+    let exp = toml_err.to_string();
+    let err: Error = toml_err.into();
+    assert!(matches!(err, Error::Toml { .. }));
+    assert_eq!(exp, err.to_string());
+}
+
 #[test]
 fn io() {
     use std::io;
@@ -120,3 +148,29 @@ fn missing() {
         Error::Missing("thing").to_string()
     )
 }
+
+#[test]
+fn future_date() {
+    use chrono::prelude::*;
+    let date = Utc.with_ymd_and_hms(2525, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        "release date 2525-01-01 00:00:00 UTC is in the future",
+        Error::FutureDate(date).to_string()
+    )
+}
+
+#[test]
+fn duplicate_key() {
+    assert_eq!(
+        "duplicate key \"license\"",
+        Error::DuplicateKey("license".to_string()).to_string()
+    )
+}
+
+#[test]
+fn patch_not_object() {
+    assert_eq!(
+        "patch 2 is not a JSON object",
+        Error::PatchNotObject(2).to_string()
+    )
+}
@@ -2,31 +2,83 @@ use std::{
     env,
     error::Error,
     ffi::OsString,
+    fmt,
     fs::File,
     io::{self, Write},
     process::ExitCode,
 };
 
-use pgxn_meta::valid::Validator;
+use pgxn_meta::{dist, valid::Validator};
 use serde_json::Value;
 
+// Exit codes for each category of failure. Success exits with 0.
+const EXIT_INVALID: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_IO: u8 = 3;
+
 // Minimal main function; logical is all in run.
-fn main() -> Result<ExitCode, Box<dyn Error>> {
-    run(io::stdout(), env::args_os())
+fn main() -> ExitCode {
+    match run(io::stdout(), io::stdin(), env::args_os()) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(e.code)
+        }
+    }
+}
+
+// Failure wraps an error with the exit code for its category: EXIT_INVALID,
+// EXIT_USAGE, or EXIT_IO.
+#[derive(Debug)]
+struct Failure {
+    code: u8,
+    err: Box<dyn Error>,
+}
+
+impl Failure {
+    fn new(code: u8, err: impl Into<Box<dyn Error>>) -> Self {
+        Failure {
+            code,
+            err: err.into(),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.err.fmt(f)
+    }
 }
 
-// Run the validator. Output will be sent to `out` and options will be parsed
-// from `args`.
-fn run<I>(mut out: impl Write, args: I) -> Result<ExitCode, Box<dyn Error>>
+impl Error for Failure {}
+
+// Run the validator. Output will be sent to `out`, options will be parsed
+// from `args`, and `stdin` will be read when the file is `-`.
+fn run<I>(mut out: impl Write, stdin: impl io::Read, args: I) -> Result<ExitCode, Failure>
 where
     I: IntoIterator,
     I::Item: Into<OsString>,
 {
-    let res = parse_args(&mut out, args)?;
-    if !res.exit {
+    let res = parse_args(&mut out, args).map_err(|e| Failure::new(EXIT_USAGE, e))?;
+    if res.exit {
+        // parse_args() printed help or the version, so there's nothing to do.
+    } else if let Some(name) = &res.init {
+        // Write a new file rather than validate one.
+        if res.file == STDIN {
+            return Err(Failure::new(EXIT_USAGE, "Cannot create '-' with --init"));
+        }
+        init(&res.file, name)?;
+        writeln!(out, "{} created", res.file).map_err(|e| Failure::new(EXIT_IO, e))?;
+    } else {
         // parse_args() doesn't need to exit, so do the thing.
-        validate(&res.file)?;
-        writeln!(out, "{} is OK", &res.file)?;
+        let name = if res.file == STDIN {
+            validate_reader(STDIN_NAME, stdin, res.release)?;
+            STDIN_NAME
+        } else {
+            validate(&res.file, res.release)?;
+            &res.file
+        };
+        writeln!(out, "{name} is OK").map_err(|e| Failure::new(EXIT_IO, e))?;
     }
 
     // If we got here, wer were successful.
@@ -37,11 +89,17 @@ where
 struct Args {
     exit: bool,
     file: String,
+    init: Option<String>,
+    release: bool,
 }
 
 // The default name of the file to validate.
 const META_FILE: &str = "META.json";
 
+// The file name that means read from STDIN, and the name to report for it.
+const STDIN: &str = "-";
+const STDIN_NAME: &str = "<stdin>";
+
 // Parses the arguments in `args` and returns Args. Output is sent to `out`.
 // If `Args.exit` is true, the caller should do no more processing.
 fn parse_args<I>(out: &mut impl Write, args: I) -> Result<Args, Box<dyn Error>>
@@ -53,6 +111,8 @@ where
     let mut res = Args {
         exit: false,
         file: String::from(META_FILE),
+        init: None,
+        release: false,
     };
     let mut parser = lexopt::Parser::from_iter(args);
 
@@ -70,6 +130,8 @@ where
                 docs(out)?;
                 res.exit = true
             }
+            Short('r') | Long("release") => res.release = true,
+            Long("init") => res.init = Some(parser.value()?.string()?),
             // Last one wins. Raise an error instead?
             Value(val) => res.file = val.string()?,
             _ => return Err(Box::new(arg.unexpected())),
@@ -79,21 +141,52 @@ where
     Ok(res)
 }
 
-// Validates `file`. Panics on validation failure.
-fn validate(file: &str) -> Result<(), Box<dyn Error>> {
+// Writes a template META.json for distribution `name` to `file`. Fails if
+// `file` already exists.
+fn init(file: &str, name: &str) -> Result<(), Failure> {
+    let io_err = |e: io::Error| Failure::new(EXIT_IO, format!("Cannot create '{file}': {e}"));
+    let mut f = File::create_new(file).map_err(io_err)?;
+    serde_json::to_writer_pretty(&mut f, &dist::template(name))
+        .map_err(|e| Failure::new(EXIT_IO, e))?;
+    writeln!(f).map_err(io_err)
+}
+
+// Validates `file`, as release metadata if `release` is true. Returns a
+// Failure with EXIT_IO if `file` cannot be read and EXIT_INVALID if it is
+// invalid.
+fn validate(file: &str, release: bool) -> Result<(), Failure> {
     match File::open(file) {
-        Ok(f) => {
-            let meta: Value = serde_json::from_reader(f)?;
-            let mut v = Validator::new();
-            if let Err(e) = v.validate(&meta) {
-                return Err(format!("{file} {e}").into());
-            };
-            Ok(())
-        }
-        Err(e) => Err(format!("Cannot open '{file}': {e}").into()),
+        Ok(f) => validate_reader(file, f, release),
+        Err(e) => Err(Failure::new(EXIT_IO, format!("Cannot open '{file}': {e}"))),
     }
 }
 
+// Validates the JSON read from `reader`, reporting errors for `name`.
+// Validates it as release metadata if `release` is true or the JSON has a
+// `certs` key, including the signed payload of v2 releases.
+fn validate_reader(name: &str, reader: impl io::Read, release: bool) -> Result<(), Failure> {
+    let meta: Value = serde_json::from_reader(reader).map_err(|e| {
+        let code = if e.is_io() { EXIT_IO } else { EXIT_INVALID };
+        Failure::new(code, e)
+    })?;
+    let mut v = Validator::new();
+    let res = if release || meta.get("certs").is_some() {
+        // v1 releases have no certs to validate.
+        v.validate_release(&meta)
+            .and_then(|version| match meta.get("certs") {
+                Some(certs) => v.validate_certs(certs),
+                None if version == 2 => v.validate_certs(&Value::Null),
+                None => Ok(()),
+            })
+    } else {
+        v.validate(&meta).map(|_| ())
+    };
+    if let Err(e) = res {
+        return Err(Failure::new(EXIT_INVALID, format!("{name} {e}")));
+    };
+    Ok(())
+}
+
 // Returns the binary name from the argument parser and falls back on the name
 // determined at compile time.
 macro_rules! bn {
@@ -106,10 +199,12 @@ macro_rules! bn {
 fn usage(out: &mut impl Write, p: &lexopt::Parser) -> Result<(), Box<dyn Error>> {
     writeln!(
         out,
-        "Usage: {} [--help | h] [--version | -v] [<path>]\n\n\
+        "Usage: {} [--help | h] [--version | -v] [--release | -r] [--init <name>] [<path>]\n\n\
         Options:\n\
-        \x20 -h --help     Print this usage statement and exit\n\
-        \x20 -v --version  Print the version number and exit",
+        \x20 -h --help         Print this usage statement and exit\n\
+        \x20 -v --version      Print the version number and exit\n\
+        \x20 -r --release      Validate <path> as PGXN release metadata\n\
+        \x20    --init <name>  Create <path> for a new distribution named <name>",
         bn!(p),
     )?;
     Ok(())
@@ -131,6 +226,7 @@ fn docs(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
 mod tests {
     use super::*;
     use core::panic;
+    use serde_json::json;
     use std::{ffi::OsStr, path::Path, str};
 
     struct TC<'a> {
@@ -156,20 +252,24 @@ mod tests {
                 args: &["meta", "-h"],
                 exit: true,
                 file: META_FILE,
-                out: "Usage: meta [--help | h] [--version | -v] [<path>]\n\n\
+                out: "Usage: meta [--help | h] [--version | -v] [--release | -r] [--init <name>] [<path>]\n\n\
                     Options:\n\
-                    \x20 -h --help     Print this usage statement and exit\n\
-                    \x20 -v --version  Print the version number and exit\n",
+                    \x20 -h --help         Print this usage statement and exit\n\
+                    \x20 -v --version      Print the version number and exit\n\
+                    \x20 -r --release      Validate <path> as PGXN release metadata\n\
+                    \x20    --init <name>  Create <path> for a new distribution named <name>\n",
             },
             TC {
                 name: "long help",
                 args: &["meta", "--help"],
                 exit: true,
                 file: META_FILE,
-                out: "Usage: meta [--help | h] [--version | -v] [<path>]\n\n\
+                out: "Usage: meta [--help | h] [--version | -v] [--release | -r] [--init <name>] [<path>]\n\n\
                     Options:\n\
-                    \x20 -h --help     Print this usage statement and exit\n\
-                    \x20 -v --version  Print the version number and exit\n",
+                    \x20 -h --help         Print this usage statement and exit\n\
+                    \x20 -v --version      Print the version number and exit\n\
+                    \x20 -r --release      Validate <path> as PGXN release metadata\n\
+                    \x20    --init <name>  Create <path> for a new distribution named <name>\n",
             },
             TC {
                 name: "short version",
@@ -220,11 +320,32 @@ mod tests {
                 Ok(res) => {
                     assert_eq!(res.exit, tc.exit);
                     assert_eq!(res.file, tc.file);
+                    assert_eq!(res.init, None);
+                    assert!(!res.release);
                     assert_eq!(str::from_utf8(&file)?, tc.out);
                 }
             }
         }
 
+        // Test --init.
+        let mut file: Vec<u8> = Vec::new();
+        let res = parse_args(&mut file, ["meta", "--init", "widget", "widget.json"])?;
+        assert!(!res.exit);
+        assert_eq!("widget.json", res.file);
+        assert_eq!(Some("widget".to_string()), res.init);
+        match parse_args(&mut file, ["meta", "--init"]) {
+            Ok(_) => panic!("Should have failed on --init without a value"),
+            Err(e) => assert_eq!(e.to_string(), "missing argument for option '--init'"),
+        }
+
+        // Test --release.
+        for args in [["meta", "--release"], ["meta", "-r"]] {
+            let res = parse_args(&mut file, args)?;
+            assert!(!res.exit);
+            assert!(res.release);
+            assert_eq!(META_FILE, res.file);
+        }
+
         // Make sure we get an error for an unknown option.
         let mut file: Vec<u8> = Vec::new();
         match parse_args(&mut file, ["hi", "-x"]) {
@@ -265,7 +386,7 @@ mod tests {
             },
         ] {
             let mut file: Vec<u8> = Vec::new();
-            match run(&mut file, tc.args) {
+            match run(&mut file, io::empty(), tc.args) {
                 Err(e) => panic!("test {:} failed: {e}", tc.name),
                 Ok(_) => {
                     assert_eq!(str::from_utf8(&file)?, tc.out);
@@ -273,6 +394,80 @@ mod tests {
             }
         }
 
+        // Read from STDIN.
+        let mut file: Vec<u8> = Vec::new();
+        run(&mut file, File::open(&meta)?, ["xyz", "-"])?;
+        assert_eq!("<stdin> is OK\n", str::from_utf8(&file)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_release() -> Result<(), Box<dyn Error>> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+        let release = dir.join("release").join("pair.json");
+        let ok_output = format!("{} is OK\n", release.display());
+
+        // Validate with --release and with auto-detection.
+        for args in [
+            vec![
+                OsStr::new("xyz"),
+                OsStr::new("--release"),
+                release.as_os_str(),
+            ],
+            vec![OsStr::new("xyz"), OsStr::new("-r"), release.as_os_str()],
+            vec![OsStr::new("xyz"), release.as_os_str()],
+        ] {
+            let mut file: Vec<u8> = Vec::new();
+            run(&mut file, io::empty(), args)?;
+            assert_eq!(ok_output, str::from_utf8(&file)?);
+        }
+
+        // Read a release from STDIN.
+        let mut file: Vec<u8> = Vec::new();
+        run(&mut file, File::open(&release)?, ["xyz", "-r", "-"])?;
+        assert_eq!("<stdin> is OK\n", str::from_utf8(&file)?);
+
+        // Validate a v1 release, which has no certs.
+        let mut meta: Value =
+            serde_json::from_reader(File::open(dir.join("v1").join("widget.json"))?)?;
+        let obj = meta.as_object_mut().unwrap();
+        obj.insert("user".to_string(), json!("theory"));
+        obj.insert("date".to_string(), json!("2019-09-23T17:16:45Z"));
+        obj.insert(
+            "sha1".to_string(),
+            json!("0389be689af6992b4da520ec510d147bae411e8b"),
+        );
+        let mut v1_release = tempfile::NamedTempFile::new()?;
+        serde_json::to_writer(&mut v1_release, &meta)?;
+        let mut file: Vec<u8> = Vec::new();
+        let args = [
+            OsStr::new("xyz"),
+            OsStr::new("--release"),
+            v1_release.path().as_os_str(),
+        ];
+        run(&mut file, io::empty(), args)?;
+        assert_eq!(
+            format!("{} is OK\n", v1_release.path().display()),
+            str::from_utf8(&file)?
+        );
+
+        // A plain distribution is not a valid release.
+        let meta = dir.join("v2").join("minimal.json");
+        let mut file: Vec<u8> = Vec::new();
+        let args = [OsStr::new("xyz"), OsStr::new("--release"), meta.as_os_str()];
+        match run(&mut file, io::empty(), args) {
+            Ok(_) => panic!("Should have failed on distribution with --release"),
+            Err(e) => {
+                assert_eq!(EXIT_INVALID, e.code);
+                assert!(
+                    e.to_string().starts_with(&meta.display().to_string()),
+                    "{e}"
+                );
+            }
+        }
+        assert!(file.is_empty());
+
         Ok(())
     }
 
@@ -284,7 +479,7 @@ mod tests {
             .join("v2")
             .join("minimal.json");
 
-        match validate(meta.as_os_str().to_str().unwrap()) {
+        match validate(meta.as_os_str().to_str().unwrap(), false) {
             Ok(_) => (),
             Err(e) => panic!("Validation failed: {e}"),
         }
@@ -293,13 +488,19 @@ mod tests {
         let meta = Path::new(env!("CARGO_MANIFEST_DIR"))
             .join("corpus")
             .join("invalid.json");
-        match validate(meta.as_os_str().to_str().unwrap()) {
+        match validate(meta.as_os_str().to_str().unwrap(), false) {
             Ok(_) => panic!("Should have failed on invalid.json but did not"),
             Err(e) => assert!(e.to_string().contains(" missing properties 'version")),
         }
 
+        // Invalid reader.
+        match validate_reader(STDIN_NAME, File::open(&meta)?, false) {
+            Ok(_) => panic!("Should have failed on invalid reader but did not"),
+            Err(e) => assert!(e.to_string().starts_with("<stdin> ")),
+        }
+
         // Nonexistent file
-        match validate("nonesuch.txt") {
+        match validate("nonesuch.txt", false) {
             Ok(_) => panic!("Should have failed unknown file"),
             Err(e) => assert!(e.to_string().starts_with("Cannot open 'nonesuch.txt': ")),
         }
@@ -308,13 +509,108 @@ mod tests {
     }
 
     #[test]
-    fn test_main() {
-        match main() {
-            Ok(_) => panic!("Should have failed on main() but did not"),
+    fn test_exit_codes() -> Result<(), Box<dyn Error>> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+        let invalid = dir.join("invalid.json");
+        let mut not_json = tempfile::NamedTempFile::new()?;
+        write!(not_json, "{{")?;
+
+        for (name, args, code) in [
+            ("missing file", vec![OsStr::new("nonesuch.json")], EXIT_IO),
+            ("bad flag", vec![OsStr::new("-x")], EXIT_USAGE),
+            (
+                "unparseable file",
+                vec![not_json.path().as_os_str()],
+                EXIT_INVALID,
+            ),
+            ("invalid file", vec![invalid.as_os_str()], EXIT_INVALID),
+        ] {
+            let mut file: Vec<u8> = Vec::new();
+            let args = [OsStr::new("xyz")].into_iter().chain(args);
+            match run(&mut file, io::empty(), args) {
+                Ok(_) => panic!("{name} should have failed but did not"),
+                Err(e) => assert_eq!(code, e.code, "{name}: {e}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_init() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let meta = dir.path().join(META_FILE);
+        let args = [
+            OsStr::new("xyz"),
+            OsStr::new("--init"),
+            OsStr::new("widget"),
+        ];
+
+        // Create the file.
+        let mut file: Vec<u8> = Vec::new();
+        run(
+            &mut file,
+            io::empty(),
+            args.iter().chain([&meta.as_os_str()]),
+        )?;
+        assert_eq!(
+            format!("{} created\n", meta.display()),
+            str::from_utf8(&file)?
+        );
+        let val: Value = serde_json::from_reader(File::open(&meta)?)?;
+        assert_eq!(dist::template("widget"), val);
+        validate(meta.to_str().unwrap(), false)?;
+
+        // Refuse to overwrite it.
+        let mut file: Vec<u8> = Vec::new();
+        match run(
+            &mut file,
+            io::empty(),
+            args.iter().chain([&meta.as_os_str()]),
+        ) {
+            Ok(_) => panic!("Should have failed to overwrite {}", meta.display()),
+            Err(e) => {
+                assert_eq!(EXIT_IO, e.code);
+                let msg = format!("Cannot create '{}': ", meta.display());
+                assert!(e.to_string().starts_with(&msg), "{e}");
+            }
+        }
+        assert!(file.is_empty());
+
+        // Refuse to create a file named for STDIN.
+        let mut file: Vec<u8> = Vec::new();
+        match run(
+            &mut file,
+            io::empty(),
+            args.iter().chain([&OsStr::new(STDIN)]),
+        ) {
+            Ok(_) => panic!("Should have failed to create {STDIN}"),
             Err(e) => {
-                assert!(e.to_string().starts_with("Cannot open "));
+                assert_eq!(EXIT_USAGE, e.code);
+                assert_eq!("Cannot create '-' with --init", e.to_string());
             }
         }
-        assert!(main().is_err());
+        assert!(file.is_empty());
+        assert!(!std::path::Path::new(STDIN).exists());
+
+        // Don't create the file with --help or --version.
+        let meta = dir.path().join("help.json");
+        for flag in ["--help", "-h", "--version", "-v"] {
+            let mut file: Vec<u8> = Vec::new();
+            run(
+                &mut file,
+                io::empty(),
+                args.iter().chain([&OsStr::new(flag), &meta.as_os_str()]),
+            )?;
+            assert!(!file.is_empty(), "{flag}");
+            assert!(!meta.exists(), "{flag} created {}", meta.display());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_main() {
+        assert_eq!(ExitCode::from(EXIT_IO), main());
     }
 }
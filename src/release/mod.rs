@@ -18,11 +18,11 @@ It supports both the [v1] and [v2] specs.
 */
 
 use crate::{dist::*, error::Error, util};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use hex;
 use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
-use std::{borrow::Borrow, collections::HashMap, fs::File, io, path::Path};
+use std::{borrow::Borrow, collections::BTreeMap, fs::File, io, path::Path};
 
 mod v1;
 mod v2;
@@ -67,50 +67,111 @@ impl Digests {
 
     /// Validates `file` against one or more of the digests. Returns an error
     /// on validation failure.
-    fn _validate<P: io::Read + io::Seek>(&self, mut file: P) -> Result<(), Error> {
+    fn _validate<P: io::Read>(&self, file: P) -> Result<(), Error> {
+        self._verify(file).map(|_| ())
+    }
+
+    /// Hashes the contents of `file` with each algorithm for which there is
+    /// a digest, reading it only once, and compares the results to the
+    /// digests. Returns the names of the algorithms that matched, or an error
+    /// on the first mismatch, checking SHA-512 first and SHA-1 last.
+    fn _verify<P: io::Read>(&self, mut file: P) -> Result<Vec<&'static str>, Error> {
         use sha1::Sha1;
         use sha2::{Digest, Sha256, Sha512};
-        let mut ok = false;
+        let mut sha512 = self.sha512.map(|_| Sha512::new());
+        let mut sha256 = self.sha256.map(|_| Sha256::new());
+        let mut sha1 = self.sha1.map(|_| Sha1::new());
+
+        // Feed each chunk to all of the hashers.
+        let mut buf = [0u8; 8192];
+        loop {
+            let n = match file.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if let Some(hasher) = sha512.as_mut() {
+                hasher.update(&buf[..n]);
+            }
+            if let Some(hasher) = sha256.as_mut() {
+                hasher.update(&buf[..n]);
+            }
+            if let Some(hasher) = sha1.as_mut() {
+                hasher.update(&buf[..n]);
+            }
+        }
+
+        let mut matched = Vec::new();
 
         // Prefer SHA-512.
-        if let Some(digest) = self.sha512() {
-            compare(&mut file, digest, Sha512::new(), "SHA-512")?;
-            ok = true;
+        if let (Some(hasher), Some(digest)) = (sha512, self.sha512()) {
+            compare(&hasher.finalize(), digest, "SHA-512")?;
+            matched.push("SHA-512");
         }
 
         // Allow SHA-256.
-        if let Some(digest) = self.sha256() {
-            compare(&mut file, digest, Sha256::new(), "SHA-256")?;
-            ok = true;
+        if let (Some(hasher), Some(digest)) = (sha256, self.sha256()) {
+            compare(&hasher.finalize(), digest, "SHA-256")?;
+            matched.push("SHA-256");
         }
 
         // Fall back on SHA-1 for PGXN v1 distributions.
-        if let Some(digest) = self.sha1() {
-            compare(&mut file, digest, Sha1::new(), "SHA-1")?;
-            ok = true;
+        if let (Some(hasher), Some(digest)) = (sha1, self.sha1()) {
+            compare(&hasher.finalize(), digest, "SHA-1")?;
+            matched.push("SHA-1");
         }
 
-        if ok {
-            return Ok(());
+        if matched.is_empty() {
+            // This should not happen, since the validator ensures there's a digest.
+            return Err(Error::Missing("digests"));
         }
 
-        // This should not happen, since the validator ensures there's a digest.
-        Err(Error::Missing("digests"))
+        Ok(matched)
+    }
+}
+
+impl TryFrom<Value> for Digests {
+    type Error = Error;
+    /// Deserializes `digests` into [`Digests`]. Returns an
+    /// [`Error::Invalid`] naming the algorithm if a digest is not a hex
+    /// string of the correct length for that algorithm, such as a 63
+    /// character SHA-256 digest, rather than the generic serde error.
+    ///
+    /// ``` rust
+    /// use serde_json::json;
+    /// use pgxn_meta::release::Digests;
+    ///
+    /// let digests = Digests::try_from(json!({"sha256": "0b68ee2c"}));
+    /// assert_eq!(
+    ///     "invalid v2 sha256 value: \"0b68ee2c\"",
+    ///     digests.unwrap_err().to_string(),
+    /// );
+    /// ```
+    fn try_from(digests: Value) -> Result<Self, Self::Error> {
+        Digests::deserialize(&digests).map_err(|e| invalid_digest(&digests).unwrap_or(e.into()))
     }
 }
 
-/// Use `hasher` to hash the contents of `file` and compare the result to
-/// `digest`. Returns an error on digest failure.
-fn compare<P, D>(mut file: P, digest: &[u8], mut hasher: D, alg: &'static str) -> Result<(), Error>
-where
-    P: io::Read + io::Seek,
-    D: digest::Digest + io::Write,
-{
-    // Rewind the file, as it may be read multiple times.
-    file.rewind()?;
-    io::copy(&mut file, &mut hasher)?;
-    let hash = hasher.finalize();
-    if constant_time_eq::constant_time_eq(hash.as_slice(), digest) {
+/// Returns an [`Error::Invalid`] naming the first digest in `digests` that
+/// is not a hex string of the length required by its algorithm. Returns
+/// `None` if all the digests are valid. Used to give context to serde
+/// errors, which do not say which digest failed.
+fn invalid_digest(digests: &Value) -> Option<Error> {
+    [("sha1", 20), ("sha256", 32), ("sha512", 64)]
+        .into_iter()
+        .find_map(|(alg, size)| {
+            let val = digests.get(alg).filter(|v| !v.is_null())?;
+            match val.as_str().map(hex::decode) {
+                Some(Ok(bytes)) if bytes.len() == size => None,
+                _ => Some(Error::Invalid(alg, 2, val.clone())),
+            }
+        })
+}
+
+/// Compares `hash` to `digest`. Returns an error on digest failure.
+fn compare(hash: &[u8], digest: &[u8], alg: &'static str) -> Result<(), Error> {
+    if constant_time_eq::constant_time_eq(hash, digest) {
         return Ok(());
     }
     Err(Error::Digest(alg, hex::encode(hash), hex::encode(digest)))
@@ -145,8 +206,19 @@ impl ReleasePayload {
     pub fn digests(&self) -> &Digests {
         self.digests.borrow()
     }
+
+    /// Returns true if the release date is later than `now` by more than
+    /// [`DATE_TOLERANCE`], which usually indicates a clock error.
+    pub fn is_future_dated(&self, now: DateTime<Utc>) -> bool {
+        self.date > now + DATE_TOLERANCE
+    }
 }
 
+/// The amount of time a release date may exceed the current time before
+/// [`ReleasePayload::is_future_dated`] considers it to be in the future,
+/// to allow for minor clock skew.
+pub const DATE_TOLERANCE: TimeDelta = TimeDelta::minutes(5);
+
 /**
 
 Represents metadata for a PGXN release, which is the same as [`Distribution`]
@@ -157,7 +229,7 @@ plus [`ReleasePayload`] that contains signed metadata about the release to PGXN.
 pub struct Release {
     #[serde(flatten)]
     dist: Distribution,
-    certs: HashMap<String, Value>,
+    certs: BTreeMap<String, Value>,
     #[serde(skip_serializing)]
     release: ReleasePayload,
 }
@@ -174,7 +246,7 @@ impl<'de> Deserialize<'de> for Release {
         struct ReleaseInitial {
             #[serde(flatten)]
             dist: Distribution,
-            certs: HashMap<String, Value>,
+            certs: BTreeMap<String, Value>,
         }
         let rel = ReleaseInitial::deserialize(deserializer)?;
 
@@ -203,7 +275,10 @@ impl<'de> Deserialize<'de> for Release {
         Ok(Release {
             dist: rel.dist,
             certs: rel.certs,
-            release: serde_json::from_value(pay).map_err(de::Error::custom)?,
+            release: ReleasePayload::deserialize(&pay).map_err(|e| {
+                let digests = pay.get("digests").unwrap_or(&Value::Null);
+                invalid_digest(digests).map_or(de::Error::custom(e), de::Error::custom)
+            })?,
         })
     }
 }
@@ -243,6 +318,12 @@ impl Release {
         self.dist.version()
     }
 
+    /// Returns the canonical `name@version` identifier for the
+    /// Distribution, as by [`Distribution::id`].
+    pub fn id(&self) -> String {
+        self.dist.id()
+    }
+
     /// Borrows the Distribution abstract.
     pub fn abs_tract(&self) -> &str {
         self.dist.abs_tract()
@@ -308,18 +389,167 @@ impl Release {
         self.release.borrow()
     }
 
+    /// Validates that the release date is not in the future relative to
+    /// `now`, allowing for [`DATE_TOLERANCE`]. Returns an error if it is.
+    pub fn validate_date(&self, now: DateTime<Utc>) -> Result<(), Error> {
+        if self.release.is_future_dated(now) {
+            return Err(Error::FutureDate(self.release.date));
+        }
+        Ok(())
+    }
+
+    /// Validates the Distribution portion of the Release against the
+    /// distribution schema, independent of the release schema. Returns an
+    /// error if the inner Distribution is invalid.
+    pub fn validate_inner_distribution(&self) -> Result<(), Error> {
+        let meta = serde_json::to_value(&self.dist)?;
+        let mut v = crate::valid::Validator::new();
+        v.validate(&meta)?;
+        Ok(())
+    }
+
+    /// Compares the Release to `other` by release date and then by version.
+    /// Releases of different distributions remain comparable, since only
+    /// their dates and versions are compared, which is why Release does not
+    /// implement [`Ord`]. Use [`sort_releases`] to sort a slice of releases
+    /// into a timeline.
+    pub fn cmp_by_date(&self, other: &Release) -> std::cmp::Ordering {
+        self.release
+            .date()
+            .cmp(other.release.date())
+            .then_with(|| self.version().cmp(other.version()))
+    }
+
+    /// Returns true if the Release has the same content as `other`,
+    /// ignoring the `pgxn` JWS in `certs` except for its decoded payload.
+    /// Compares the inner [`Distribution`], the decoded [`ReleasePayload`],
+    /// and any other certifications, so that a re-signed release compares
+    /// equal to the original.
+    pub fn eq_ignoring_signature(&self, other: &Release) -> bool {
+        let not_pgxn = |(key, _): &(&String, &Value)| key.as_str() != "pgxn";
+        self.dist == other.dist
+            && self.release == other.release
+            && self
+                .certs
+                .iter()
+                .filter(not_pgxn)
+                .eq(other.certs.iter().filter(not_pgxn))
+    }
+
+    /// Returns whether the URL of a `source` artifact in the Distribution
+    /// matches the release payload URI. The URI is relative to a PGXN
+    /// mirror, as in `dist/pair/0.1.8/pair-0.1.8.zip`, while the artifact
+    /// URL usually points elsewhere, such as to a GitHub release, so they
+    /// match if they have the same file name, ignoring any query string or
+    /// fragment in the URL. Returns `Some(true)` if any `source` artifact
+    /// matches, and `None` if there is no `source` artifact or the URI has
+    /// no file name.
+    pub fn source_artifact_matches_uri(&self) -> Option<bool> {
+        let name = file_name(self.release.uri()).filter(|n| !n.is_empty())?;
+        let mut sources = self
+            .artifacts()?
+            .iter()
+            .filter(|a| a.kind() == "source")
+            .peekable();
+        sources.peek()?;
+        Some(sources.any(|a| file_name(a.url()) == Some(name)))
+    }
+
+    /// Returns the `typ` header parameter of the `pgxn` JWS in `certs`,
+    /// which declares the media type of the JWS. Returns `None` if the JWS
+    /// header has no `typ` or cannot be decoded.
+    pub fn header_typ(&self) -> Option<String> {
+        let header = self.jose_header().ok()?;
+        header.get("typ")?.as_str().map(String::from)
+    }
+
+    /// Checks that the `typ` header parameter of the `pgxn` JWS in `certs`
+    /// is `expected`, so that a verifier can confirm that the JWS is a PGXN
+    /// release signature and not some other JWS. Compares the values
+    /// case-insensitively, as [RFC 7515] recommends for media types.
+    /// Returns an error if the header cannot be decoded or has no `typ`, or
+    /// if `typ` does not match `expected`.
+    ///
+    /// [RFC 7515]: https://datatracker.ietf.org/doc/html/rfc7515#section-4.1.9
+    pub fn expects_typ(&self, expected: &str) -> Result<(), Error> {
+        match self.jose_header()?.get("typ") {
+            Some(Value::String(typ)) if typ.eq_ignore_ascii_case(expected) => Ok(()),
+            Some(typ) => Err(Error::Invalid("typ", 2, typ.clone())),
+            None => Err(Error::Missing("typ")),
+        }
+    }
+
+    /// Decodes the JOSE header of the `pgxn` JWS in `certs`: the union of
+    /// the Base 64 URL-encoded `protected` header and the unprotected
+    /// `header` object. Uses the first signature of a JWS in the general
+    /// JSON serialization syntax. Returns an error if the protected header
+    /// cannot be decoded.
+    fn jose_header(&self) -> Result<serde_json::Map<String, Value>, Error> {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+        let Some(mut jws) = self.certs.get("pgxn") else {
+            return Err(Error::Missing("pgxn"));
+        };
+        if let Some(sig) = jws.get("signatures").and_then(|sigs| sigs.get(0)) {
+            jws = sig;
+        }
+
+        let mut header = serde_json::Map::new();
+        if let Some(protected) = jws.get("protected") {
+            let invalid = || Error::Invalid("protected", 2, protected.clone());
+            let b64 = protected.as_str().ok_or_else(invalid)?;
+            let json = URL_SAFE_NO_PAD.decode(b64).map_err(|_| invalid())?;
+            match serde_json::from_slice(&json)? {
+                Value::Object(obj) => header = obj,
+                _ => return Err(invalid()),
+            }
+        }
+        if let Some(Value::Object(unprotected)) = jws.get("header") {
+            for (key, val) in unprotected {
+                header.entry(key).or_insert_with(|| val.clone());
+            }
+        }
+        Ok(header)
+    }
+
     /// Borrows the Distribution certifications.
-    pub fn certs(&self) -> &HashMap<String, Value> {
+    pub fn certs(&self) -> &BTreeMap<String, Value> {
         self.certs.borrow()
     }
 
     /// Borrows the custom_props object, which holds any `x_` or `X_`
     /// properties
-    pub fn custom_props(&self) -> &HashMap<String, Value> {
+    pub fn custom_props(&self) -> &BTreeMap<String, Value> {
         self.dist.custom_props()
     }
 }
 
+/// Returns the file name at the end of the path in `url`, ignoring any
+/// query string or fragment.
+fn file_name(url: &str) -> Option<&str> {
+    url.split(['?', '#']).next()?.rsplit('/').next()
+}
+
+/// Sorts `releases` by release date and then by version, as determined by
+/// [`Release::cmp_by_date`].
+pub fn sort_releases(releases: &mut [Release]) {
+    releases.sort_by(Release::cmp_by_date);
+}
+
+impl std::fmt::Display for Release {
+    /// fmt writes a one-line summary of the Release to f: the
+    /// [`Distribution`] summary followed by the user who released it and
+    /// the release date. Use the Debug format for full detail.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} released by {} on {}",
+            self.dist,
+            self.release.user(),
+            self.release.date().format("%Y-%m-%dT%H:%M:%SZ"),
+        )
+    }
+}
+
 impl TryFrom<Value> for Release {
     type Error = Error;
     /// Converts the PGXN release `META.json` data from `meta` into a
@@ -423,7 +653,7 @@ impl TryFrom<&[Value]> for Release {
         }
 
         // Find the version of the first doc.
-        let version = util::get_version(&meta[0]).ok_or_else(|| Error::UnknownSpec)?;
+        let version = util::get_version(&meta[0])?;
 
         // Convert the first doc to v2 if necessary.
         let mut v2 = match version {
@@ -1,5 +1,5 @@
 use super::*;
-use crate::error::Error;
+use crate::{error::Error, tests::v2::minimal_v2};
 use chrono::prelude::*;
 use serde_json::{json, Value};
 use std::{fs::File, io::Cursor, io::Write, path::PathBuf};
@@ -73,7 +73,7 @@ fn test_corpus() -> Result<(), Error> {
                             release.license(),
                             "{v_dir}/{bn} license",
                         );
-                        let certs: HashMap<String, Value> =
+                        let certs: BTreeMap<String, Value> =
                             serde_json::from_value(meta.get("certs").unwrap().clone()).unwrap();
                         assert_eq!(&certs, release.certs(), "{v_dir}/{bn} release certs");
                         assert_eq!(
@@ -406,6 +406,53 @@ fn digests() {
     }
 }
 
+#[test]
+fn digests_try_from() -> Result<(), Error> {
+    let sha256 = "0b68ee2ce5b2c0641c6c429ed2ce17e2ed76ddd58bf9a16e698c5069d60aa34e";
+    let dig = Digests::try_from(json!({"sha256": sha256}))?;
+    assert_eq!(sha256, hex::encode(dig.sha256().unwrap()));
+
+    for (name, json, alg, val) in [
+        (
+            "short sha256",
+            json!({"sha256": &sha256[1..]}),
+            "sha256",
+            json!(&sha256[1..]),
+        ),
+        ("long sha1", json!({"sha1": sha256}), "sha1", json!(sha256)),
+        (
+            "non-hex sha512",
+            json!({"sha512": "xyz"}),
+            "sha512",
+            json!("xyz"),
+        ),
+        ("number", json!({"sha256": 42}), "sha256", json!(42)),
+        (
+            "second bad",
+            json!({"sha256": sha256, "sha512": sha256}),
+            "sha512",
+            json!(sha256),
+        ),
+    ] {
+        match Digests::try_from(json) {
+            Ok(_) => panic!("{name} unexpectedly succeeded"),
+            Err(e) => assert_eq!(
+                Error::Invalid(alg, 2, val.clone()).to_string(),
+                e.to_string(),
+                "{name}"
+            ),
+        }
+    }
+
+    // Other errors pass through.
+    match Digests::try_from(json!("sha256")) {
+        Ok(_) => panic!("string unexpectedly succeeded"),
+        Err(e) => assert!(matches!(e, Error::Serde { .. }), "{e}"),
+    }
+
+    Ok(())
+}
+
 #[test]
 fn digest_validation() {
     use sha1::Sha1;
@@ -538,6 +585,39 @@ fn digest_validation() {
     }
 }
 
+#[test]
+fn digest_single_pass() -> Result<(), Error> {
+    use sha1::Sha1;
+    use sha2::{Digest, Sha256, Sha512};
+    let data = b"So I featuring a. g. cook".repeat(1024);
+
+    for (name, digests, exp) in [
+        (
+            "all three",
+            Digests {
+                sha1: Some(Sha1::digest(&data).into()),
+                sha256: Some(Sha256::digest(&data).into()),
+                sha512: Some(Sha512::digest(&data).into()),
+            },
+            vec!["SHA-512", "SHA-256", "SHA-1"],
+        ),
+        (
+            "sha1 and sha256",
+            Digests {
+                sha1: Some(Sha1::digest(&data).into()),
+                sha256: Some(Sha256::digest(&data).into()),
+                sha512: None,
+            },
+            vec!["SHA-256", "SHA-1"],
+        ),
+    ] {
+        // A byte slice cannot be rewound, so it must be read only once.
+        assert_eq!(exp, digests._verify(data.as_slice())?, "{name}");
+    }
+
+    Ok(())
+}
+
 #[test]
 fn digest_file_validation() {
     let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus", "v2"]
@@ -581,6 +661,324 @@ fn release_payload() {
     )
 }
 
+#[test]
+fn release_future_dated() -> Result<(), Error> {
+    let pay: ReleasePayload = serde_json::from_value(payload())?;
+    let date = release_date();
+    for (name, now, exp) in [
+        ("past", date + TimeDelta::days(1), false),
+        ("now", date, false),
+        ("within tolerance", date - TimeDelta::minutes(4), false),
+        ("beyond tolerance", date - TimeDelta::minutes(6), true),
+        ("one year ahead", date - TimeDelta::days(365), true),
+    ] {
+        assert_eq!(exp, pay.is_future_dated(now), "{name}");
+    }
+
+    // Test Release::validate_date.
+    let mut meta = minimal_v2()?;
+    json_patch::merge(&mut meta, &certs());
+    let release = Release::try_from(meta)?;
+    let date = *release.release().date();
+    release.validate_date(date)?;
+    release.validate_date(date + TimeDelta::days(30))?;
+    match release.validate_date(date - TimeDelta::days(365)) {
+        Ok(_) => panic!("future date unexpectedly passed"),
+        Err(e) => assert_eq!(
+            format!("release date {date} is in the future"),
+            e.to_string()
+        ),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn release_inner_distribution() -> Result<(), Error> {
+    let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus", "v2"]
+        .iter()
+        .collect();
+    let glob = Glob::new("*.json")?;
+
+    for path in glob.walk(dir) {
+        let path = path?.into_path();
+        let mut meta: Value = serde_json::from_reader(File::open(&path)?)?;
+        json_patch::merge(&mut meta, &certs());
+        let release = Release::try_from(meta)?;
+        if let Err(e) = release.validate_inner_distribution() {
+            panic!("{} failed: {e}", path.display());
+        }
+    }
+
+    // Deserialize an invalid distribution without validation.
+    let mut meta = minimal_v2()?;
+    json_patch::merge(&mut meta, &certs());
+    meta["abstract"] = json!("");
+    let release: Release = serde_json::from_value(meta)?;
+    match release.validate_inner_distribution() {
+        Ok(_) => panic!("invalid inner distribution unexpectedly passed"),
+        Err(e) => assert!(e.to_string().contains("abstract")),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn release_sorting() -> Result<(), Error> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    let meta = minimal_v2()?;
+
+    // Create a release for each name, version, and date.
+    let mut releases = Vec::new();
+    for (name, version, date) in [
+        ("pair", "0.2.0", "2024-09-13T17:32:55Z"),
+        ("widget", "1.0.0", "2023-01-01T00:00:00Z"),
+        ("pair", "0.1.0", "2024-09-13T17:32:55Z"),
+    ] {
+        let mut pay = payload();
+        pay["date"] = json!(date);
+        let mut meta = meta.clone();
+        json_patch::merge(&mut meta, &certs());
+        meta["name"] = json!(name);
+        meta["version"] = json!(version);
+        meta["certs"]["pgxn"]["payload"] = json!(URL_SAFE_NO_PAD.encode(pay.to_string()));
+        releases.push(Release::try_from(meta)?);
+    }
+
+    use std::cmp::Ordering;
+    assert_eq!(Ordering::Greater, releases[0].cmp_by_date(&releases[1]));
+    assert_eq!(Ordering::Greater, releases[0].cmp_by_date(&releases[2]));
+    assert_eq!(Ordering::Less, releases[1].cmp_by_date(&releases[2]));
+    assert_eq!(Ordering::Equal, releases[0].cmp_by_date(&releases[0]));
+
+    sort_releases(&mut releases);
+    assert_eq!(
+        vec!["widget-1.0.0", "pair-0.1.0", "pair-0.2.0"],
+        releases
+            .iter()
+            .map(|r| format!("{}-{}", r.name(), r.version()))
+            .collect::<Vec<_>>(),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn release_display() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    json_patch::merge(&mut meta, &certs());
+    let release = Release::try_from(meta)?;
+    let str = release.to_string();
+    for part in [
+        "pair",
+        "0.1.8",
+        "PostgreSQL",
+        "theory",
+        "2024-09-13T17:32:55Z",
+    ] {
+        assert!(str.contains(part), "{str} missing {part}");
+    }
+    assert_eq!(
+        "pair 0.1.8 — A key/value pair data type (PostgreSQL) released by theory on 2024-09-13T17:32:55Z",
+        str,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn release_id() -> Result<(), Error> {
+    let mut meta = minimal_v2()?;
+    json_patch::merge(&mut meta, &certs());
+    let release = Release::try_from(meta)?;
+    assert_eq!("pair@0.1.8", release.id());
+    let (name, version) = crate::dist::parse_id(&release.id())?;
+    assert_eq!(release.name(), name);
+    assert_eq!(release.version(), &version);
+
+    Ok(())
+}
+
+#[test]
+fn release_eq_ignoring_signature() -> Result<(), Error> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    let mut meta = minimal_v2()?;
+    json_patch::merge(&mut meta, &certs());
+    let release = Release::try_from(meta.clone())?;
+
+    // Re-sign with a different signature and protected header.
+    let mut resigned = meta.clone();
+    resigned["certs"]["pgxn"]["signature"] =
+        json!("cC4hiUPoj9Eetdgtv3hF80EGrhuB__dzERat0XF9g2VtQgr9PJbu3XOiZj5RZmh7");
+    resigned["certs"]["pgxn"]["protected"] = json!("eyJhbGciOiJFUzI1NiJ9");
+    let resigned = Release::try_from(resigned)?;
+    assert_ne!(release, resigned);
+    assert!(release.eq_ignoring_signature(&resigned));
+    assert!(resigned.eq_ignoring_signature(&release));
+
+    // Re-encode the same payload with different key order.
+    let mut reencoded = meta.clone();
+    let payload = URL_SAFE_NO_PAD
+        .decode(meta["certs"]["pgxn"]["payload"].as_str().unwrap())
+        .unwrap();
+    let payload: Value = serde_json::from_slice(&payload)?;
+    let mut keys: Vec<_> = payload.as_object().unwrap().iter().collect();
+    keys.reverse();
+    let json = format!(
+        "{{{}}}",
+        keys.iter()
+            .map(|(k, v)| format!("{}:{v}", json!(k)))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    reencoded["certs"]["pgxn"]["payload"] = json!(URL_SAFE_NO_PAD.encode(json));
+    let reencoded = Release::try_from(reencoded)?;
+    assert_ne!(release.certs(), reencoded.certs());
+    assert!(release.eq_ignoring_signature(&reencoded));
+
+    // Change the payload, distribution, and other certs.
+    let mut pay = payload.clone();
+    pay["user"] = json!("anna");
+    let mut new_payload = meta.clone();
+    new_payload["certs"]["pgxn"]["payload"] = json!(URL_SAFE_NO_PAD.encode(pay.to_string()));
+    let mut new_dist = meta.clone();
+    new_dist["abstract"] = json!("A pair of keys and values");
+    let mut new_certs = meta.clone();
+    new_certs["certs"]["x_yz"] = json!(false);
+
+    for (name, other) in [
+        ("payload", new_payload),
+        ("distribution", new_dist),
+        ("other certs", new_certs),
+    ] {
+        let other = Release::try_from(other)?;
+        assert!(!release.eq_ignoring_signature(&other), "{name}");
+        assert!(!other.eq_ignoring_signature(&release), "{name} reversed");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn release_header_typ() -> Result<(), Error> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    let mut meta = minimal_v2()?;
+    json_patch::merge(&mut meta, &certs());
+    let encode = |header: Value| json!(URL_SAFE_NO_PAD.encode(header.to_string()));
+
+    // No header at all.
+    let release = Release::try_from(meta.clone())?;
+    assert_eq!(None, release.header_typ());
+    match release.expects_typ("pgxn+jws") {
+        Ok(_) => panic!("Should have failed without typ"),
+        Err(e) => assert_eq!("typ property missing", e.to_string()),
+    }
+
+    // Protected header.
+    let mut with_typ = meta.clone();
+    with_typ["certs"]["pgxn"]["protected"] = encode(json!({"alg": "ES256", "typ": "pgxn+jws"}));
+    let release = Release::try_from(with_typ)?;
+    assert_eq!(Some("pgxn+jws".to_string()), release.header_typ());
+    release.expects_typ("pgxn+jws")?;
+    release.expects_typ("PGXN+JWS")?;
+    match release.expects_typ("JWT") {
+        Ok(_) => panic!("Should have failed on mismatched typ"),
+        Err(e) => assert_eq!(r#"invalid v2 typ value: "pgxn+jws""#, e.to_string()),
+    }
+
+    // Unprotected header.
+    let mut unprotected = meta.clone();
+    unprotected["certs"]["pgxn"]["protected"] = encode(json!({"alg": "ES256"}));
+    unprotected["certs"]["pgxn"]["header"] = json!({"typ": "JWT"});
+    let release = Release::try_from(unprotected)?;
+    assert_eq!(Some("JWT".to_string()), release.header_typ());
+    release.expects_typ("jwt")?;
+
+    // Undecodable protected header.
+    let mut bad = meta.clone();
+    bad["certs"]["pgxn"]["protected"] = json!("eyJhbGciOiJFUzI1NiJ");
+    let release = Release::try_from(bad)?;
+    assert_eq!(None, release.header_typ());
+    assert!(release.expects_typ("JWT").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn release_source_artifact_matches_uri() -> Result<(), Error> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    let mut meta = minimal_v2()?;
+    json_patch::merge(&mut meta, &certs());
+    let sha = "b353b5a82b3b54e95f4a2859e7a2bd0648abcb35a7c3612b126c2c75438fc2f8e8ee1f19e61f30fa54d7bb64bcf217ed1264722b497bcb613f82d78751515b67";
+    let github = "https://github.com/theory/kv-pair/releases/download/v0.1.7/pair-0.1.7.zip";
+    let pgxn = "dist/pair/0.1.7/pair-0.1.7.zip";
+
+    for (name, uri, artifacts, exp) in [
+        ("no artifacts", pgxn, json!(null), None),
+        (
+            "no source",
+            pgxn,
+            json!([{"type": "binary", "url": github, "sha512": sha}]),
+            None,
+        ),
+        (
+            "relative match",
+            pgxn,
+            json!([{"type": "source", "url": github, "sha512": sha}]),
+            Some(true),
+        ),
+        (
+            "relative query",
+            pgxn,
+            json!([{"type": "source", "url": format!("{github}?raw=true"), "sha512": sha}]),
+            Some(true),
+        ),
+        (
+            "relative mismatch",
+            "dist/pair/0.1.8/pair-0.1.8.zip",
+            json!([{"type": "source", "url": github, "sha512": sha}]),
+            Some(false),
+        ),
+        (
+            "second source matches",
+            pgxn,
+            json!([
+                {"type": "source", "url": "https://example.com/pair.tar.gz", "sha512": sha},
+                {"type": "source", "url": github, "sha512": sha},
+            ]),
+            Some(true),
+        ),
+        (
+            "no file name",
+            "dist/pair/0.1.7/",
+            json!([{"type": "source", "url": github, "sha512": sha}]),
+            None,
+        ),
+        (
+            "binary matches",
+            pgxn,
+            json!([
+                {"type": "source", "url": "https://example.com/pair.tar.gz", "sha512": sha},
+                {"type": "binary", "url": github, "sha512": sha},
+            ]),
+            Some(false),
+        ),
+    ] {
+        let mut meta = meta.clone();
+        let mut pay = payload();
+        pay["uri"] = json!(uri);
+        meta["certs"]["pgxn"]["payload"] = json!(URL_SAFE_NO_PAD.encode(pay.to_string()));
+        meta["artifacts"] = artifacts;
+        if meta["artifacts"].is_null() {
+            meta.as_object_mut().unwrap().remove("artifacts");
+        }
+        let release: Release = serde_json::from_value(meta)?;
+        assert_eq!(exp, release.source_artifact_matches_uri(), "{name}");
+    }
+
+    Ok(())
+}
+
 #[test]
 fn release() -> Result<(), Error> {
     let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus", "v2"]
@@ -604,7 +1002,7 @@ fn release() -> Result<(), Error> {
             Err(e) => panic!("{name} failed: {e}"),
             Ok(rel) => {
                 // Should have the certs.
-                let certs: HashMap<String, Value> =
+                let certs: BTreeMap<String, Value> =
                     serde_json::from_value(patch.get("certs").unwrap().clone())?;
                 assert_eq!(&certs, rel.certs(), "{name} certs");
                 // Should have the release payload.
@@ -753,7 +1151,7 @@ fn release_deserialize_err() -> Result<(), Error> {
 /// Extracts the subset of val (which must be an instance of Value::Object)
 /// where the property names start with `x_` or `X_`. Used for testing
 /// custom_props.
-fn exes_from(val: &Value) -> HashMap<String, Value> {
+fn exes_from(val: &Value) -> BTreeMap<String, Value> {
     val.as_object()
         .unwrap()
         .into_iter()
@@ -1,7 +1,8 @@
 use super::Release;
-use crate::error::Error;
+use crate::{dist::v2::invalid_contents_item, error::Error};
+use serde::Deserialize;
 use serde_json::Value;
 
 pub fn from_value(meta: Value) -> Result<Release, Error> {
-    Ok(serde_json::from_value(meta)?)
+    Release::deserialize(&meta).map_err(|e| invalid_contents_item(&meta).unwrap_or(e.into()))
 }
@@ -1,6 +1,6 @@
 mod common;
 mod v1;
-mod v2;
+pub(crate) mod v2;
 
 #[test]
 fn test_readme_example() {
@@ -2739,7 +2739,16 @@ fn test_v2_artifacts() -> Result<(), Error> {
     Ok(())
 }
 
-fn valid_v2_distribution() -> Value {
+/// Loads `corpus/v2/minimal.json`.
+pub(crate) fn minimal_v2() -> Result<Value, Error> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("corpus")
+        .join("v2")
+        .join("minimal.json");
+    Ok(serde_json::from_reader(std::fs::File::open(path)?)?)
+}
+
+pub(crate) fn valid_v2_distribution() -> Value {
     json!({
       "name": "pgTAP",
       "abstract": "Unit testing for PostgreSQL",
@@ -1,28 +1,36 @@
+use crate::error::Error;
 use serde_json::Value;
 
-// get_version returns the major version from the value stored in
-// `meta-spec.version` in `meta`. Returns None if the field does not exist or
-// does not contain a valid version (either 1 or 2).
-pub fn get_version(meta: &Value) -> Option<u8> {
-    let v = meta.get("meta-spec")?.get("version")?.as_str()?;
-    if v.len() < 2 {
-        return None;
-    }
-    match &v[0..2] {
-        "1." => Some(1),
-        "2." => Some(2),
-        _ => None,
+/// Returns the major version from the value stored in `meta-spec.version` in
+/// `meta`, either 1 or 2. Returns [`Error::UnsupportedSpec`] if the value
+/// is a later major version, such as `3.0.0`, and [`Error::UnknownSpec`] if
+/// the field does not exist or does not contain a complete SemVer version.
+/// Partial versions such as `2.` are not accepted, so that any version
+/// accepted here also deserializes into a [`Spec`](crate::dist::Spec).
+pub fn get_version(meta: &Value) -> Result<u8, Error> {
+    let v = spec_version_full(meta).ok_or(Error::UnknownSpec)?;
+    match u8::try_from(v.major) {
+        Ok(v @ (1 | 2)) => Ok(v),
+        Ok(v) if v > 0 => Err(Error::UnsupportedSpec(v)),
+        _ => Err(Error::UnknownSpec),
     }
 }
 
+/// Returns the complete SemVer version from the value stored in
+/// `meta-spec.version` in `meta`, such as `2.0.1`. Returns `None` if the
+/// field does not exist or does not contain a complete SemVer version.
+pub fn spec_version_full(meta: &Value) -> Option<semver::Version> {
+    let v = meta.get("meta-spec")?.get("version")?.as_str()?;
+    semver::Version::parse(v).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
-    #[test]
-    fn test_get_version() {
-        for (name, meta, expect) in [
+    fn get_version_cases() -> Vec<(&'static str, Value, Option<u8>)> {
+        vec![
             (
                 "1.0.0",
                 json!({"meta-spec": { "version": "1.0.0"}}),
@@ -38,7 +46,7 @@ mod tests {
                 json!({"meta-spec": { "version": "1.1.0"}}),
                 Some(1),
             ),
-            ("1.", json!({"meta-spec": { "version": "1."}}), Some(1)),
+            ("1.", json!({"meta-spec": { "version": "1."}}), None),
             (
                 "2.0.0",
                 json!({"meta-spec": { "version": "2.0.0"}}),
@@ -54,8 +62,9 @@ mod tests {
                 json!({"meta-spec": { "version": "2.1.0"}}),
                 Some(2),
             ),
-            ("2.", json!({"meta-spec": { "version": "2."}}), Some(2)),
+            ("2.", json!({"meta-spec": { "version": "2."}}), None),
             ("3.", json!({"meta-spec": { "version": "3."}}), None),
+            ("2.0", json!({"meta-spec": { "version": "2.0"}}), None),
             ("3.0.0", json!({"meta-spec": { "version": "3.0.0"}}), None),
             ("9.0.0", json!({"meta-spec": { "version": "9.0.0"}}), None),
             ("too short", json!({"meta-spec": { "version": "1"}}), None),
@@ -69,8 +78,136 @@ mod tests {
             ("meta-spec array", json!({"meta-spec": [1]}), None),
             ("no meta-spec", json!({}), None),
             ("root array", json!([1]), None),
+        ]
+    }
+
+    #[test]
+    fn test_get_version() {
+        for (name, meta, expect) in get_version_cases() {
+            assert_eq!(expect, get_version(&meta).ok(), "{name}")
+        }
+    }
+
+    #[test]
+    fn test_get_version_spec_agree() {
+        use crate::dist::Spec;
+        for (name, meta, _) in get_version_cases() {
+            let spec = meta
+                .get("meta-spec")
+                .and_then(|spec| serde_json::from_value::<Spec>(spec.clone()).ok());
+            match get_version(&meta) {
+                Ok(v) => {
+                    let spec = spec.unwrap_or_else(|| panic!("{name}: Spec failed"));
+                    assert_eq!(u64::from(v), spec.version().major, "{name}");
+                }
+                Err(Error::UnsupportedSpec(v)) => {
+                    let spec = spec.unwrap_or_else(|| panic!("{name}: Spec failed"));
+                    assert_eq!(u64::from(v), spec.version().major, "{name}");
+                }
+                Err(_) => assert!(spec.is_none(), "{name}: Spec unexpectedly parsed"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_version_unsupported() {
+        for (name, version, expect) in [
+            ("1.0.0", json!("1.0.0"), Ok(1)),
+            ("2.", json!("2."), Err("cannot determine meta-spec version")),
+            ("2.1.0", json!("2.1.0"), Ok(2)),
+            (
+                "3.0.0",
+                json!("3.0.0"),
+                Err("unsupported meta-spec version 3"),
+            ),
+            ("3.", json!("3."), Err("cannot determine meta-spec version")),
+            (
+                "12.1.0",
+                json!("12.1.0"),
+                Err("unsupported meta-spec version 12"),
+            ),
+            (
+                "0.0",
+                json!("0.0"),
+                Err("cannot determine meta-spec version"),
+            ),
+            (
+                "leading zero",
+                json!("02.0.0"),
+                Err("cannot determine meta-spec version"),
+            ),
+            (
+                "no dot",
+                json!("3"),
+                Err("cannot determine meta-spec version"),
+            ),
+            (
+                "huge",
+                json!("300.0.0"),
+                Err("cannot determine meta-spec version"),
+            ),
+            (
+                "letters",
+                json!("v3.0.0"),
+                Err("cannot determine meta-spec version"),
+            ),
+            (
+                "number",
+                json!(3),
+                Err("cannot determine meta-spec version"),
+            ),
         ] {
-            assert_eq!(expect, get_version(&meta), "{name}")
+            let meta = json!({"meta-spec": {"version": version}});
+            assert_eq!(
+                expect.map_err(String::from),
+                get_version(&meta).map_err(|e| e.to_string()),
+                "{name}"
+            );
         }
+        assert!(matches!(get_version(&json!({})), Err(Error::UnknownSpec)));
+        assert!(matches!(
+            get_version(&json!({"meta-spec": {"version": "3.0.0"}})),
+            Err(Error::UnsupportedSpec(3))
+        ));
+    }
+
+    #[test]
+    fn test_spec_version_full() {
+        use semver::Version;
+        for (name, meta, expect) in [
+            (
+                "1.0.0",
+                json!({"meta-spec": { "version": "1.0.0"}}),
+                Some(Version::new(1, 0, 0)),
+            ),
+            (
+                "2.0.1",
+                json!({"meta-spec": { "version": "2.0.1"}}),
+                Some(Version::new(2, 0, 1)),
+            ),
+            (
+                "2.1.0-beta1",
+                json!({"meta-spec": { "version": "2.1.0-beta1"}}),
+                Version::parse("2.1.0-beta1").ok(),
+            ),
+            (
+                "3.0.0",
+                json!({"meta-spec": { "version": "3.0.0"}}),
+                Some(Version::new(3, 0, 0)),
+            ),
+            ("2.", json!({"meta-spec": { "version": "2."}}), None),
+            ("2.0", json!({"meta-spec": { "version": "2.0"}}), None),
+            ("empty string", json!({"meta-spec": { "version": ""}}), None),
+            ("number", json!({"meta-spec": { "version": 2}}), None),
+            ("no version", json!({"meta-spec": {}}), None),
+            ("no meta-spec", json!({}), None),
+        ] {
+            assert_eq!(expect, spec_version_full(&meta), "{name}")
+        }
+
+        // get_version rejects what spec_version_full rejects.
+        let meta = json!({"meta-spec": { "version": "2."}});
+        assert_eq!(None, get_version(&meta).ok());
+        assert_eq!(None, spec_version_full(&meta));
     }
 }
@@ -5,27 +5,41 @@ use boon::Compiler;
 use relative_path::{Component, RelativePath};
 use serde_json::Value;
 
-/// new returns a new boon::Compiler with the schema files loaded from `dir`
+/// The v1 schema files, one JSON object per line.
+pub(crate) const SCHEMAS_V1: &str =
+    include_str!(concat!(env!("OUT_DIR"), "/pgxn-meta-v1.schemas.json"));
+
+/// The v2 schema files, one JSON object per line.
+pub(crate) const SCHEMAS_V2: &str =
+    include_str!(concat!(env!("OUT_DIR"), "/pgxn-meta-v2.schemas.json"));
+
+/// new returns a new boon::Compiler with the v1 and v2 schema files loaded
 /// and configured to validate `path` and `license` formats.
 pub fn new() -> Compiler {
-    let schema_v1 = include_str!(concat!(env!("OUT_DIR"), "/pgxn-meta-v1.schemas.json"));
-    let schema_v2 = include_str!(concat!(env!("OUT_DIR"), "/pgxn-meta-v2.schemas.json"));
     let mut compiler = spec_compiler();
-
-    for str in [schema_v1, schema_v2] {
-        for line in str.lines() {
-            let schema: Value = serde_json::from_str(line).unwrap();
-            let id = &schema["$id"]
-                .as_str()
-                .ok_or(Error::UnknownSchemaId)
-                .unwrap();
-            compiler.add_resource(id, schema.to_owned()).unwrap();
-        }
+    for src in [SCHEMAS_V1, SCHEMAS_V2] {
+        add_schemas(&mut compiler, src).unwrap();
     }
-
     compiler
 }
 
+/// Adds each schema in `src` to `compiler` under its `$id`. `src` may
+/// contain a single schema document, which may span multiple lines, or
+/// multiple schemas, one JSON object per line, as in [`SCHEMAS_V1`] and
+/// [`SCHEMAS_V2`]. Returns an error if `src` contains invalid JSON or a
+/// schema without an `$id`.
+pub fn add_schemas(compiler: &mut Compiler, src: &str) -> Result<(), Error> {
+    for schema in serde_json::Deserializer::from_str(src).into_iter::<Value>() {
+        let schema = schema?;
+        let id = schema["$id"]
+            .as_str()
+            .ok_or(Error::UnknownSchemaId)?
+            .to_string();
+        compiler.add_resource(&id, schema)?;
+    }
+    Ok(())
+}
+
 /// Creates a new boon::compiler with format assertions enabled and validation
 /// for the custom `path` and `license` formats.
 pub fn spec_compiler() -> Compiler {
@@ -213,6 +227,45 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_add_schemas() -> Result<(), Error> {
+        // Load each version's JSON lines through add_schemas.
+        for (version, src) in [("v1", SCHEMAS_V1), ("v2", SCHEMAS_V2)] {
+            let mut compiler = spec_compiler();
+            add_schemas(&mut compiler, src)?;
+            let mut schemas = Schemas::new();
+            let id = format!("https://pgxn.org/meta/{version}/distribution.schema.json");
+            compiler.compile(&id, &mut schemas)?;
+        }
+
+        // Load a single, multi-line schema document.
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("schema")
+            .join("v2")
+            .join("semver.schema.json");
+        let src = std::fs::read_to_string(path)?;
+        assert!(src.trim().lines().count() > 1);
+        let mut compiler = spec_compiler();
+        add_schemas(&mut compiler, &src)?;
+        let mut schemas = Schemas::new();
+        let idx = compiler.compile("https://pgxn.org/meta/v2/semver.schema.json", &mut schemas)?;
+        assert!(schemas.validate(&json!("1.2.3"), idx).is_ok());
+
+        // Test errors.
+        let mut compiler = spec_compiler();
+        for (name, src, err) in [
+            ("no id", "{\"type\": \"string\"}", "no $id found in schema"),
+            ("bad json", "{\"$id\": ", "EOF while parsing"),
+        ] {
+            match add_schemas(&mut compiler, src) {
+                Ok(_) => panic!("{name} unexpectedly succeeded"),
+                Err(e) => assert!(e.to_string().contains(err), "{name}: {e}"),
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_new() -> Result<(), Error> {
         let mut compiler = new();
@@ -39,7 +39,14 @@ assert!(validator.validate(&meta).is_ok());
 */
 use crate::{error::Error, util};
 use boon::{Compiler, Schemas};
-use serde_json::Value;
+use serde::{
+    de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor},
+    Serialize,
+};
+use serde_json::{Map, Value};
+use std::io::{self, Read};
+
+pub use crate::util::spec_version_full;
 
 /// Export compiler publicly only for tests.
 #[cfg(test)]
@@ -80,6 +87,31 @@ impl Validator {
         }
     }
 
+    /// Creates and returns a new Validator with the distribution, release,
+    /// and payload schemas for all Meta spec versions compiled up front, so
+    /// that the first validation need not pay the cost of compilation.
+    /// Returns an error if any schema fails to compile.
+    ///
+    /// ``` rust
+    /// use pgxn_meta::valid::*;
+    /// let validator = Validator::new_preloaded();
+    /// assert!(validator.is_ok());
+    /// ```
+    pub fn new_preloaded() -> Result<Validator, Error> {
+        let mut validator = Validator::new();
+        for (v, schema) in [
+            (1, "distribution.schema.json"),
+            (1, "release.schema.json"),
+            (2, "distribution.schema.json"),
+            (2, "release.schema.json"),
+            (2, "payload.schema.json"),
+        ] {
+            let id = format!("{SCHEMA_BASE}{v}/{schema}");
+            validator.compiler.compile(&id, &mut validator.schemas)?;
+        }
+        Ok(validator)
+    }
+
     /// Validates PGXN distribution metadata.
     ///
     /// Load a distribution `META.json` file into a serde_json::value::Value
@@ -91,6 +123,133 @@ impl Validator {
         self.validate_schema(meta, "distribution.schema.json")
     }
 
+    /// Reads and validates PGXN distribution metadata from `reader`, reading
+    /// no more than `max_bytes` bytes. Returns the Meta spec version (1 or 2)
+    /// on success and an error if the input exceeds `max_bytes`, cannot be
+    /// parsed, or fails validation. Use it to guard against huge
+    /// `META.json` files from untrusted sources.
+    ///
+    /// ``` rust
+    /// use pgxn_meta::valid::*;
+    ///
+    /// let meta = r#"{"meta-spec": {"version": "2.0.0"}}"#;
+    /// let mut validator = Validator::new();
+    /// match validator.validate_reader_limited(meta.as_bytes(), 10) {
+    ///     Ok(_) => panic!("Should have failed"),
+    ///     Err(e) => assert_eq!("input too large", e.to_string()),
+    /// }
+    /// ```
+    pub fn validate_reader_limited<R: io::Read>(
+        &mut self,
+        reader: R,
+        max_bytes: usize,
+    ) -> Result<u8, Error> {
+        // Read one byte more than the limit to detect oversized input.
+        let limit = u64::try_from(max_bytes).unwrap_or(u64::MAX);
+        let mut buf = Vec::new();
+        reader.take(limit.saturating_add(1)).read_to_end(&mut buf)?;
+        if buf.len() > max_bytes {
+            return Err(Error::Param("input too large"));
+        }
+
+        let meta: Value = serde_json::from_slice(&buf)?;
+        self.validate(&meta)
+    }
+
+    /// Parses and validates the PGXN distribution metadata in `s`, rejecting
+    /// any object with duplicate keys. Returns the Meta spec version (1 or
+    /// 2) on success, an [`Error::DuplicateKey`] error if any object
+    /// contains a duplicate key, and a parse or validation error on other
+    /// failures. See [`parse_strict`].
+    pub fn validate_str_strict(&mut self, s: &str) -> Result<u8, Error> {
+        let meta = parse_strict(s)?;
+        self.validate(&meta)
+    }
+
+    /// Validates PGXN distribution metadata like [`Self::validate`], but
+    /// returns an [`Annotation`] for each failing schema keyword, suitable
+    /// for editor tooling such as a language server. Returns the Meta spec
+    /// version (1 or 2) on success. A failure that does not come from a
+    /// schema keyword, such as an unknown spec version, returns a single
+    /// Annotation with empty pointers and keyword.
+    ///
+    /// ``` rust
+    /// use pgxn_meta::valid::*;
+    /// use serde_json::json;
+    ///
+    /// let mut validator = Validator::new();
+    /// match validator.annotate(&json!({})) {
+    ///     Ok(_) => panic!("Should have failed"),
+    ///     Err(notes) => assert_eq!(
+    ///         "cannot determine meta-spec version",
+    ///         notes[0].message(),
+    ///     ),
+    /// }
+    /// ```
+    pub fn annotate(&mut self, meta: &Value) -> Result<u8, Vec<Annotation>> {
+        let v = util::get_version(meta).map_err(Annotation::for_error)?;
+        let id = format!("{SCHEMA_BASE}{v}/distribution.schema.json");
+        let idx = self
+            .compiler
+            .compile(&id, &mut self.schemas)
+            .map_err(|e| Annotation::for_error(e.into()))?;
+
+        match self.schemas.validate(meta, idx) {
+            Ok(()) => Ok(v),
+            Err(e) => {
+                let mut notes = Vec::new();
+                if let Ok(output) = serde_json::to_value(e.detailed_output()) {
+                    collect_annotations(&output, &mut notes);
+                }
+                if notes.is_empty() {
+                    return Err(Annotation::for_error(e.into()));
+                }
+                Err(notes)
+            }
+        }
+    }
+
+    /// Validates PGXN distribution metadata and normalizes it to v2.
+    ///
+    /// Returns the original Meta spec version (1 or 2) along with the
+    /// metadata. v1 metadata is converted to v2, while v2 metadata is
+    /// returned unchanged. Returns a validation error on failure.
+    pub fn validate_and_normalize(&mut self, meta: Value) -> Result<(u8, Value), Error> {
+        match self.validate(&meta)? {
+            1 => Ok((1, crate::dist::v1::to_v2(&meta)?)),
+            version => Ok((version, meta)),
+        }
+    }
+
+    /// Lazily validates each PGXN distribution metadata value yielded by
+    /// `iter`, returning an iterator of results in the same order. Each
+    /// value is validated only when the caller pulls its result, so a large
+    /// stream need never be collected.
+    ///
+    /// The returned iterator holds the mutable borrow of the Validator, so
+    /// the Validator cannot be used for anything else until the iterator is
+    /// dropped. The values yielded by `iter` must outlive that borrow, too.
+    ///
+    /// ``` rust
+    /// use pgxn_meta::valid::*;
+    /// use serde_json::json;
+    ///
+    /// let metas = [json!({}), json!({"meta-spec": true})];
+    /// let mut validator = Validator::new();
+    /// for res in validator.validate_iter(metas.iter()) {
+    ///     assert!(res.is_err());
+    /// }
+    /// ```
+    pub fn validate_iter<'a, I>(
+        &'a mut self,
+        iter: I,
+    ) -> impl Iterator<Item = Result<u8, Error>> + 'a
+    where
+        I: Iterator<Item = &'a Value> + 'a,
+    {
+        iter.map(move |meta| self.validate(meta))
+    }
+
     /// Validates PGXN release distribution metadata.
     ///
     /// On release, PGXN adds release metadata to the distribution `META.json`
@@ -131,12 +290,38 @@ impl Validator {
         self.validate_version_schema(meta, 2, "payload.schema.json")
     }
 
+    /// Validates a PGXN release `certs` object independently of the
+    /// distribution metadata that contains it. Validates the structure of
+    /// `certs`, including the `pgxn` JWS and its header, then decodes the
+    /// JWS payload and validates it as by [`Self::validate_payload`].
+    /// Returns an error if `certs` or the payload is invalid, or if the
+    /// payload cannot be decoded from Base 64 URL-encoded JSON. Does not
+    /// verify the signature.
+    pub fn validate_certs(&mut self, certs: &Value) -> Result<(), Error> {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+        self.validate_version_schema(certs, 2, "certs.schema.json")?;
+
+        let Some(Value::String(b64)) = certs.get("pgxn").and_then(|jws| jws.get("payload")) else {
+            return Err(Error::Missing("pgxn payload"));
+        };
+        let json = URL_SAFE_NO_PAD
+            .decode(b64)
+            .map_err(|_| Error::Invalid("pgxn payload", 2, Value::from(b64.as_str())))?;
+        let payload: Value = serde_json::from_slice(&json)?;
+        self.validate_payload(&payload)
+    }
+
     fn validate_schema(&mut self, meta: &Value, schema: &str) -> Result<u8, Error> {
-        let v = util::get_version(meta).ok_or(Error::UnknownSpec)?;
+        let v = util::get_version(meta)?;
         self.validate_version_schema(meta, v, schema).map(|()| v)
     }
 
-    fn validate_version_schema(&mut self, meta: &Value, v: u8, schema: &str) -> Result<(), Error> {
+    pub(crate) fn validate_version_schema(
+        &mut self,
+        meta: &Value,
+        v: u8,
+        schema: &str,
+    ) -> Result<(), Error> {
         let id = format!("{SCHEMA_BASE}{v}/{schema}");
 
         let compiler = &mut self.compiler;
@@ -148,6 +333,445 @@ impl Validator {
     }
 }
 
+/// Describes a single validation failure reported by
+/// [`Validator::annotate`].
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Annotation {
+    instance_pointer: String,
+    schema_pointer: String,
+    keyword: String,
+    message: String,
+}
+
+impl Annotation {
+    /// Returns a single Annotation with the message of `e` and empty
+    /// pointers and keyword.
+    fn for_error(e: Error) -> Vec<Annotation> {
+        vec![Annotation {
+            instance_pointer: String::new(),
+            schema_pointer: String::new(),
+            keyword: String::new(),
+            message: e.to_string(),
+        }]
+    }
+
+    /// Borrows the JSON Pointer to the invalid value in the instance, such
+    /// as `/maintainers/0/email`. Empty for the root.
+    pub fn instance_pointer(&self) -> &str {
+        self.instance_pointer.as_str()
+    }
+
+    /// Borrows the absolute location of the failing keyword in the schema,
+    /// such as
+    /// `https://pgxn.org/meta/v2/maintainers.schema.json#/items/properties/email/format`.
+    pub fn schema_pointer(&self) -> &str {
+        self.schema_pointer.as_str()
+    }
+
+    /// Borrows the name of the failing keyword, such as `required` or
+    /// `format`.
+    pub fn keyword(&self) -> &str {
+        self.keyword.as_str()
+    }
+
+    /// Borrows the human-readable error message.
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+
+    /// Formats the Annotation as a single line that prefixes the message
+    /// with the instance pointer, as in `at '/version': message`. Returns
+    /// just the message for an Annotation that does not come from a schema
+    /// keyword.
+    fn line(&self) -> String {
+        if self.keyword.is_empty() {
+            return self.message.clone();
+        }
+        format!("at '{}': {}", self.instance_pointer, self.message)
+    }
+}
+
+/// Appends an [`Annotation`] to `notes` for each leaf error in `unit`, a
+/// JSON Schema output unit in the [detailed output format].
+///
+/// [detailed output format]: https://json-schema.org/draft/2020-12/json-schema-core#section-12.4.3
+fn collect_annotations(unit: &Value, notes: &mut Vec<Annotation>) {
+    if let Some(Value::Array(errors)) = unit.get("errors") {
+        for err in errors {
+            collect_annotations(err, notes);
+        }
+        return;
+    }
+
+    let Some(Value::String(message)) = unit.get("error") else {
+        return;
+    };
+    let str = |key| unit.get(key).and_then(Value::as_str).unwrap_or_default();
+    let keyword_location = str("keywordLocation");
+    let schema_pointer = match str("absoluteKeywordLocation") {
+        "" => keyword_location,
+        abs => abs,
+    };
+    notes.push(Annotation {
+        instance_pointer: str("instanceLocation").to_string(),
+        schema_pointer: schema_pointer.to_string(),
+        keyword: keyword_location
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        message: message.to_string(),
+    });
+}
+
+/// Returns the JSON Schema files for version `version` of the PGXN Meta
+/// spec, exactly as embedded in this build and used for validation. Useful
+/// for generating documentation or validating in another language. Returns
+/// [`Error::UnsupportedSpec`] for versions other than 1 and 2.
+///
+/// ``` rust
+/// use pgxn_meta::valid::schemas;
+///
+/// let schemas = schemas(2).unwrap();
+/// assert!(schemas.iter().any(|s| s["$id"] == "https://pgxn.org/meta/v2/distribution.schema.json"));
+/// ```
+pub fn schemas(version: u8) -> Result<Vec<Value>, Error> {
+    let lines = match version {
+        1 => compiler::SCHEMAS_V1,
+        2 => compiler::SCHEMAS_V2,
+        v => return Err(Error::UnsupportedSpec(v)),
+    };
+    lines
+        .lines()
+        .map(|line| serde_json::from_str(line).map_err(Error::from))
+        .collect()
+}
+
+/// Validates `meta` and returns a list of Cargo [build script warnings], one
+/// for each validation failure. Returns an empty vector if `meta` is valid.
+/// Useful in `build.rs` scripts that generate `META.json` files, such as
+/// those for [pgrx] extensions, so that failures show up in build output.
+///
+/// ``` rust
+/// use serde_json::json;
+/// use pgxn_meta::valid::cargo_warnings;
+///
+/// let warnings = cargo_warnings(&json!({"meta-spec": {"version": "2.0.0"}}));
+/// assert!(!warnings.is_empty());
+/// for w in warnings {
+///     println!("{w}");
+/// }
+/// ```
+///
+/// [build script warnings]: https://doc.rust-lang.org/cargo/reference/build-scripts.html#cargo-warning
+/// [pgrx]: https://github.com/pgcentralfoundation/pgrx
+pub fn cargo_warnings(meta: &Value) -> Vec<String> {
+    let mut validator = Validator::new();
+    let Err(notes) = validator.annotate(meta) else {
+        return Vec::new();
+    };
+
+    notes
+        .iter()
+        .map(|note| format!("cargo:warning={}", note.line()))
+        .collect()
+}
+
+/// Parses and validates the PGXN distribution metadata in `s`. Returns the
+/// Meta spec version (1 or 2) on success and a list of error messages on
+/// failure, one for each validation failure. Requires no filesystem or
+/// network access, and returns only owned values, making it suitable for
+/// WASM environments.
+///
+/// ``` rust
+/// use pgxn_meta::valid::validate_str;
+///
+/// match validate_str("not json") {
+///     Ok(_) => panic!("Should have failed"),
+///     Err(errs) => assert!(!errs.is_empty()),
+/// }
+/// ```
+pub fn validate_str(s: &str) -> Result<u8, Vec<String>> {
+    let meta: Value = serde_json::from_str(s).map_err(|e| vec![e.to_string()])?;
+    let mut validator = Validator::new();
+    validator
+        .annotate(&meta)
+        .map_err(|notes| notes.iter().map(Annotation::line).collect())
+}
+
+/// Parses the JSON in `s` like [`serde_json::from_str`], but returns an
+/// [`Error::DuplicateKey`] error naming the first key that appears more than
+/// once in the same object. By default, `serde_json` silently keeps the last
+/// value for a duplicate key, which can hide a mistaken or malicious
+/// override.
+///
+/// ``` rust
+/// use pgxn_meta::valid::parse_strict;
+///
+/// match parse_strict(r#"{"name": "pair", "name": "widget"}"#) {
+///     Ok(_) => panic!("Should have failed"),
+///     Err(e) => assert_eq!(r#"duplicate key "name""#, e.to_string()),
+/// }
+/// ```
+pub fn parse_strict(s: &str) -> Result<Value, Error> {
+    let mut de = serde_json::Deserializer::from_str(s);
+    let res = StrictValue::deserialize(&mut de).and_then(|v| de.end().map(|()| v.0));
+    match res {
+        Ok(val) => Ok(val),
+        Err(e) if e.is_data() => match e.to_string().strip_prefix(DUPLICATE_KEY) {
+            Some(msg) => {
+                // Strip the position serde_json appends to the message.
+                let key = msg.rsplit_once(" at line ").map_or(msg, |(key, _)| key);
+                Err(Error::DuplicateKey(key.to_string()))
+            }
+            None => Err(e.into()),
+        },
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Parses the JSON in `s` like [`serde_json::from_str`], but first returns
+/// an [`Error::Param`] error if arrays and objects in `s` nest more than
+/// `max_depth` levels deep. Use it to guard against pathologically nested
+/// input from untrusted sources.
+///
+/// The depth check is a single pre-scan of `s` that counts brackets and
+/// braces outside of strings, so it never recurses and rejects deep input
+/// before `serde_json` starts parsing. Note that `serde_json` itself fails
+/// on input nested more than 128 levels deep, so a `max_depth` over 127
+/// cannot admit deeper input.
+///
+/// ``` rust
+/// use pgxn_meta::valid::parse_bounded;
+///
+/// assert!(parse_bounded(r#"{"a": [1, 2]}"#, 2).is_ok());
+/// match parse_bounded(r#"{"a": [[1, 2]]}"#, 2) {
+///     Ok(_) => panic!("Should have failed"),
+///     Err(e) => assert_eq!("input too deeply nested", e.to_string()),
+/// }
+/// ```
+pub fn parse_bounded(s: &str, max_depth: usize) -> Result<Value, Error> {
+    let (mut depth, mut in_str, mut escaped) = (0usize, false, false);
+    for b in s.bytes() {
+        if in_str {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_str = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_str = true,
+            b'[' | b'{' => {
+                depth += 1;
+                if depth > max_depth {
+                    return Err(Error::Param("input too deeply nested"));
+                }
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    Ok(serde_json::from_str(s)?)
+}
+
+/// Parses the JSON in `s` and returns true if every object, at any depth,
+/// has its keys in strictly ascending order, as required for canonical JSON
+/// serialization. Use it to check externally-produced canonical JSON before
+/// signing or comparing it. Works on the raw text, because a parsed
+/// [`Value`] does not reliably preserve key order. An object with duplicate
+/// keys is not canonical. Returns an error if `s` is not valid JSON.
+///
+/// ``` rust
+/// use pgxn_meta::valid::is_canonical;
+///
+/// assert!(is_canonical(r#"{"a": 1, "b": {"c": [{"d": 2, "e": 3}]}}"#).unwrap());
+/// assert!(!is_canonical(r#"{"b": 1, "a": 2}"#).unwrap());
+/// ```
+pub fn is_canonical(s: &str) -> Result<bool, Error> {
+    let mut de = serde_json::Deserializer::from_str(s);
+    let SortedKeys(sorted) = SortedKeys::deserialize(&mut de)?;
+    de.end()?;
+    Ok(sorted)
+}
+
+/// Parses the JSON in `s` and serializes it compactly with the keys of every
+/// object in sorted order, so that the result is canonical according to
+/// [`is_canonical`]. Returns an error if `s` is not valid JSON.
+///
+/// ``` rust
+/// use pgxn_meta::valid::{canonicalize, is_canonical};
+///
+/// let canon = canonicalize(r#"{"b": 1, "a": {"d": 2, "c": 3}}"#).unwrap();
+/// assert_eq!(r#"{"a":{"c":3,"d":2},"b":1}"#, canon);
+/// assert!(is_canonical(&canon).unwrap());
+/// ```
+pub fn canonicalize(s: &str) -> Result<String, Error> {
+    let meta: Value = serde_json::from_str(s)?;
+    Ok(serde_json::to_string(&Sorted::from(&meta))?)
+}
+
+/// Serializes a [`Value`] with the keys of every object in sorted order,
+/// regardless of whether [`Map`] preserves insertion order.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Sorted<'a> {
+    Object(std::collections::BTreeMap<&'a str, Sorted<'a>>),
+    Array(Vec<Sorted<'a>>),
+    Scalar(&'a Value),
+}
+
+impl<'a> From<&'a Value> for Sorted<'a> {
+    fn from(val: &'a Value) -> Self {
+        match val {
+            Value::Object(obj) => {
+                Sorted::Object(obj.iter().map(|(k, v)| (k.as_str(), v.into())).collect())
+            }
+            Value::Array(list) => Sorted::Array(list.iter().map(Sorted::from).collect()),
+            _ => Sorted::Scalar(val),
+        }
+    }
+}
+
+/// Deserializes any JSON value into true if the keys of every object in it
+/// appear in strictly ascending order.
+struct SortedKeys(bool);
+
+impl<'de> Deserialize<'de> for SortedKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(SortedKeysVisitor)
+            .map(SortedKeys)
+    }
+}
+
+struct SortedKeysVisitor;
+
+impl<'de> Visitor<'de> for SortedKeysVisitor {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<bool, E> {
+        Ok(true)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<bool, E> {
+        Ok(true)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<bool, E> {
+        Ok(true)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<bool, E> {
+        Ok(true)
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<bool, E> {
+        Ok(true)
+    }
+
+    fn visit_unit<E>(self) -> Result<bool, E> {
+        Ok(true)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<bool, A::Error> {
+        let mut sorted = true;
+        while let Some(SortedKeys(ok)) = seq.next_element()? {
+            sorted &= ok;
+        }
+        Ok(sorted)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<bool, A::Error> {
+        // Consume the whole object even once it's known to be unsorted.
+        let mut sorted = true;
+        let mut prev: Option<String> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            let SortedKeys(ok) = map.next_value()?;
+            sorted &= ok && prev.as_ref().is_none_or(|p| *p < key);
+            prev = Some(key);
+        }
+        Ok(sorted)
+    }
+}
+
+/// Prefix for the custom deserialization error raised by [`StrictValue`]
+/// for a duplicate key.
+const DUPLICATE_KEY: &str = "duplicate key: ";
+
+/// Deserializes a [`Value`] while rejecting duplicate object keys.
+struct StrictValue(Value);
+
+impl<'de> Deserialize<'de> for StrictValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(StrictVisitor).map(StrictValue)
+    }
+}
+
+struct StrictVisitor;
+
+impl<'de> Visitor<'de> for StrictVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut list = Vec::new();
+        while let Some(StrictValue(val)) = seq.next_element()? {
+            list.push(val);
+        }
+        Ok(Value::Array(list))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut obj = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if obj.contains_key(&key) {
+                return Err(de::Error::custom(format!("{DUPLICATE_KEY}{key}")));
+            }
+            let StrictValue(val) = map.next_value()?;
+            obj.insert(key, val);
+        }
+        Ok(Value::Object(obj))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,6 +865,438 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_unsupported_versions() -> Result<(), Error> {
+        let (_, v2) = load_minimal()?;
+        let mut validator = Validator::new();
+        for version in ["3.0.0", "3.1.0-beta1", "4.0.0"] {
+            let mut meta = v2.clone();
+            meta["meta-spec"]["version"] = json!(version);
+            let major = version[..1].parse::<u8>().unwrap();
+            match validator.validate(&meta) {
+                Err(Error::UnsupportedSpec(v)) => assert_eq!(major, v, "{version}"),
+                Err(e) => panic!("{version} validate returned unexpected error: {e}"),
+                Ok(_) => panic!("{version} validate unexpectedly succeeded"),
+            }
+            match validator.validate_release(&meta) {
+                Err(e) => assert_eq!(
+                    format!("unsupported meta-spec version {major}"),
+                    e.to_string(),
+                    "{version} validate_release"
+                ),
+                Ok(_) => panic!("{version} validate_release unexpectedly succeeded"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_schemas() -> Result<(), Error> {
+        for version in [1, 2] {
+            let schemas = schemas(version)?;
+            let prefix = format!("{SCHEMA_BASE}{version}/");
+            for schema in &schemas {
+                let id = schema["$id"].as_str().unwrap_or_default();
+                assert!(id.starts_with(&prefix), "v{version} {id}");
+            }
+            assert!(
+                schemas.iter().any(|s| s["$id"]
+                    .as_str()
+                    .is_some_and(|id| id.ends_with("distribution.schema.json"))),
+                "v{version} distribution schema"
+            );
+        }
+
+        // Make sure the v2 schemas match the files in the schema directory.
+        let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "schema", "v2"]
+            .iter()
+            .collect();
+        let count = Glob::new("*.schema.json")?.walk(dir).count();
+        assert_eq!(count, schemas(2)?.len());
+
+        for version in [0, 3] {
+            match schemas(version) {
+                Ok(_) => panic!("v{version} unexpectedly succeeded"),
+                Err(e) => assert!(matches!(e, Error::UnsupportedSpec(v) if v == version)),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cargo_warnings() -> Result<(), Error> {
+        let (_, v2) = load_minimal()?;
+        assert!(cargo_warnings(&v2).is_empty());
+
+        // Remove required fields.
+        let mut meta = v2.clone();
+        json_patch::merge(&mut meta, &json!({"version": null, "license": null}));
+        let warnings = cargo_warnings(&meta);
+        assert!(!warnings.is_empty());
+        for w in &warnings {
+            assert!(w.starts_with("cargo:warning=at '"), "{w}");
+        }
+        let all = warnings.join("\n");
+        assert!(all.contains("'version'"), "{all}");
+        assert!(all.contains("'license'"), "{all}");
+
+        // Unknown spec.
+        assert_eq!(
+            vec!["cargo:warning=cannot determine meta-spec version"],
+            cargo_warnings(&json!({})),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_str() -> Result<(), Error> {
+        let (v1, v2) = load_minimal()?;
+        assert_eq!(Ok(1), validate_str(&v1.to_string()));
+        assert_eq!(Ok(2), validate_str(&v2.to_string()));
+
+        // Invalid JSON.
+        match validate_str("{") {
+            Ok(_) => panic!("invalid JSON unexpectedly passed"),
+            Err(errs) => assert_eq!(1, errs.len(), "{errs:?}"),
+        }
+
+        // Unknown spec.
+        assert_eq!(
+            Err(vec!["cannot determine meta-spec version".to_string()]),
+            validate_str("{}"),
+        );
+
+        // Remove required fields.
+        let mut meta = v2.clone();
+        json_patch::merge(&mut meta, &json!({"version": null, "license": null}));
+        match validate_str(&meta.to_string()) {
+            Ok(_) => panic!("invalid metadata unexpectedly passed"),
+            Err(errs) => {
+                let all = errs.join("\n");
+                assert!(all.contains("'version'"), "{all}");
+                assert!(all.contains("'license'"), "{all}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotate() -> Result<(), Error> {
+        let (v1, v2) = load_minimal()?;
+        let mut validator = Validator::new();
+        assert_eq!(Ok(1), validator.annotate(&v1));
+        assert_eq!(Ok(2), validator.annotate(&v2));
+
+        // Unknown spec.
+        match validator.annotate(&json!({})) {
+            Ok(_) => panic!("unknown spec unexpectedly passed"),
+            Err(notes) => {
+                assert_eq!(1, notes.len(), "{notes:?}");
+                assert_eq!("", notes[0].instance_pointer());
+                assert_eq!("", notes[0].schema_pointer());
+                assert_eq!("", notes[0].keyword());
+                assert_eq!("cannot determine meta-spec version", notes[0].message());
+            }
+        }
+
+        // Fail required and format.
+        let mut meta = v2.clone();
+        json_patch::merge(
+            &mut meta,
+            &json!({"version": null, "maintainers": [{"name": "theory", "email": "nope"}]}),
+        );
+        match validator.annotate(&meta) {
+            Ok(_) => panic!("invalid metadata unexpectedly passed"),
+            Err(notes) => {
+                let required = notes
+                    .iter()
+                    .find(|n| n.keyword() == "required")
+                    .expect("required annotation");
+                assert_eq!("", required.instance_pointer());
+                assert!(
+                    required
+                        .schema_pointer()
+                        .ends_with("base.schema.json#/required"),
+                    "{}",
+                    required.schema_pointer()
+                );
+                assert!(required.message().contains("'version'"), "{required:?}");
+
+                let format = notes
+                    .iter()
+                    .find(|n| n.keyword() == "format")
+                    .expect("format annotation");
+                assert_eq!("/maintainers/0/email", format.instance_pointer());
+                assert!(
+                    format.schema_pointer().ends_with("/email/format"),
+                    "{}",
+                    format.schema_pointer()
+                );
+                assert!(format.message().contains("nope"), "{format:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_strict() -> Result<(), Error> {
+        // Parse valid JSON of all types.
+        for json in [
+            "null",
+            "true",
+            "42",
+            "-42",
+            "98.6",
+            r#""hi""#,
+            "[]",
+            "{}",
+            r#"[1, "two", {"three": [3.0, null, false]}]"#,
+            r#"{"a": {"b": {"a": 1}}, "b": [{"a": 1}, {"a": 2}]}"#,
+        ] {
+            let exp: Value = serde_json::from_str(json)?;
+            assert_eq!(exp, parse_strict(json)?, "{json}");
+        }
+
+        // Detect duplicate keys.
+        let (_, v2) = load_minimal()?;
+        let json = v2.to_string();
+        let dupe = json.replacen('{', r#"{"license": "MIT", "#, 1);
+        for (name, json, key) in [
+            ("license", dupe.as_str(), "license"),
+            ("nested", r#"{"a": {"b": 1, "b": 2}}"#, "b"),
+            ("in array", r#"[{"x": 1}, {"y": 1, "y": 1}]"#, "y"),
+            ("quoted", r#"{"a \"b\"": 1, "a \"b\"": 1}"#, r#"a "b""#),
+        ] {
+            match parse_strict(json) {
+                Ok(_) => panic!("{name} unexpectedly succeeded"),
+                Err(Error::DuplicateKey(k)) => assert_eq!(key, k, "{name}"),
+                Err(e) => panic!("{name} unexpected error: {e}"),
+            }
+        }
+
+        // Other errors pass through.
+        for json in ["{", "[1,]", "{} {}"] {
+            match parse_strict(json) {
+                Ok(_) => panic!("{json} unexpectedly succeeded"),
+                Err(e) => assert!(matches!(e, Error::Serde(_)), "{json}: {e}"),
+            }
+        }
+
+        // Test the validator.
+        let mut validator = Validator::new();
+        assert_eq!(2, validator.validate_str_strict(&json)?);
+        match validator.validate_str_strict(&dupe) {
+            Ok(_) => panic!("duplicate license unexpectedly passed"),
+            Err(e) => assert_eq!(r#"duplicate key "license""#, e.to_string()),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bounded() -> Result<(), Error> {
+        for (name, json, depth, exp) in [
+            ("scalar", "42", 0, json!(42)),
+            ("flat object", r#"{"a": 1}"#, 1, json!({"a": 1})),
+            (
+                "nested",
+                r#"{"a": [1, {"b": 2}]}"#,
+                3,
+                json!({"a": [1, {"b": 2}]}),
+            ),
+            ("siblings", "[[1], [2], [3]]", 2, json!([[1], [2], [3]])),
+            (
+                "brackets in strings",
+                r#"{"a": "[[[{{{", "b": "\\\"[["}"#,
+                1,
+                json!({"a": "[[[{{{", "b": "\\\"[["}),
+            ),
+        ] {
+            assert_eq!(exp, parse_bounded(json, depth)?, "{name}");
+        }
+
+        // Exceed the limit.
+        let deep = format!("{}{}", "[".repeat(100), "]".repeat(100));
+        for (name, json, depth) in [
+            ("object", r#"{"a": 1}"#, 0),
+            ("nested", r#"{"a": [1, {"b": 2}]}"#, 2),
+            ("deep", deep.as_str(), 64),
+            ("unbalanced", "[[[[[[", 5),
+        ] {
+            match parse_bounded(json, depth) {
+                Ok(_) => panic!("{name} unexpectedly parsed"),
+                Err(e) => assert_eq!("input too deeply nested", e.to_string(), "{name}"),
+            }
+        }
+        assert_eq!(deep.len(), parse_bounded(&deep, 100)?.to_string().len());
+
+        // Invalid JSON within the limit.
+        match parse_bounded("[[1]", 5) {
+            Ok(_) => panic!("invalid JSON unexpectedly parsed"),
+            Err(e) => assert!(matches!(e, Error::Serde { .. }), "{e}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical() -> Result<(), Error> {
+        // A sorted document is canonical.
+        for (name, json) in [
+            ("scalar", "true"),
+            ("empty object", "{}"),
+            ("flat", r#"{"a": 1, "b": null, "c": "x"}"#),
+            (
+                "nested",
+                r#"{"a": {"b": 1, "c": [{"d": 2, "e": [3.5, -1]}]}}"#,
+            ),
+            ("prefix", r#"{"a": 1, "ab": 2, "b": 3}"#),
+        ] {
+            assert!(is_canonical(json)?, "{name}");
+            assert_eq!(
+                serde_json::from_str::<Value>(json)?,
+                serde_json::from_str::<Value>(&canonicalize(json)?)?,
+                "{name}",
+            );
+        }
+
+        // An unsorted document is not canonical, but canonicalizes to an
+        // equal-valued sorted form.
+        for (name, json, exp) in [
+            ("flat", r#"{"b": 1, "a": 2}"#, r#"{"a":2,"b":1}"#),
+            (
+                "nested",
+                r#"{"a": {"z": 1, "b": [{"y": 1, "c": 2}]}}"#,
+                r#"{"a":{"b":[{"c":2,"y":1}],"z":1}}"#,
+            ),
+            (
+                "array",
+                r#"[{"a": 1}, {"c": 1, "b": 2}]"#,
+                r#"[{"a":1},{"b":2,"c":1}]"#,
+            ),
+            ("duplicate", r#"{"a": 1, "a": 2}"#, r#"{"a":2}"#),
+        ] {
+            assert!(!is_canonical(json)?, "{name}");
+            let canon = canonicalize(json)?;
+            assert_eq!(exp, canon, "{name}");
+            assert!(is_canonical(&canon)?, "{name}");
+            if name != "duplicate" {
+                assert_eq!(
+                    serde_json::from_str::<Value>(json)?,
+                    serde_json::from_str::<Value>(&canon)?,
+                    "{name}",
+                );
+            }
+        }
+
+        // Canonicalize the minimal corpus file.
+        let (_, meta) = load_minimal()?;
+        let canon = canonicalize(&meta.to_string())?;
+        assert!(is_canonical(&canon)?);
+        assert_eq!(meta, serde_json::from_str::<Value>(&canon)?);
+
+        // Invalid JSON.
+        for json in ["{", r#"{"a": 1} x"#] {
+            match is_canonical(json) {
+                Ok(_) => panic!("{json} unexpectedly parsed"),
+                Err(e) => assert!(matches!(e, Error::Serde { .. }), "{e}"),
+            }
+            assert!(canonicalize(json).is_err(), "{json}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_reader_limited() -> Result<(), Error> {
+        let mut validator = Validator::new();
+        let (v1, v2) = load_minimal()?;
+
+        for (name, meta, version) in [("v1", v1, 1), ("v2", v2, 2)] {
+            let json = serde_json::to_vec(&meta)?;
+
+            // Limit smaller than the input.
+            match validator.validate_reader_limited(json.as_slice(), json.len() - 1) {
+                Ok(_) => panic!("{name} unexpectedly succeeded"),
+                Err(e) => assert_eq!("input too large", e.to_string(), "{name}"),
+            }
+
+            // Limit exactly the size of the input.
+            let v = validator.validate_reader_limited(json.as_slice(), json.len())?;
+            assert_eq!(version, v, "{name}");
+
+            // Sufficient limit.
+            let v = validator.validate_reader_limited(json.as_slice(), 1024 * 1024)?;
+            assert_eq!(version, v, "{name}");
+        }
+
+        // Make sure parse errors are returned.
+        match validator.validate_reader_limited("{".as_bytes(), 100) {
+            Ok(_) => panic!("invalid JSON unexpectedly succeeded"),
+            Err(e) => assert!(matches!(e, Error::Serde { .. }), "{e}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_and_normalize() -> Result<(), Error> {
+        let mut validator = Validator::new();
+        let (v1, v2) = load_minimal()?;
+
+        // v1 is converted to v2.
+        let exp = crate::dist::v1::to_v2(&v1)?;
+        let (version, meta) = validator.validate_and_normalize(v1)?;
+        assert_eq!(1, version);
+        assert_eq!(exp, meta);
+        assert_eq!(2, validator.validate(&meta)?);
+
+        // v2 is unchanged.
+        let (version, meta) = validator.validate_and_normalize(v2.clone())?;
+        assert_eq!(2, version);
+        assert_eq!(v2, meta);
+
+        // Invalid metadata fails.
+        match validator.validate_and_normalize(json!({"meta-spec": {"version": "2.0.0"}})) {
+            Ok(_) => panic!("invalid metadata unexpectedly succeeded"),
+            Err(e) => assert!(matches!(e, Error::ValidationError { .. }), "{e}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_iter() -> Result<(), Error> {
+        let mut validator = Validator::new();
+        let (v1, v2) = load_minimal()?;
+        let metas = [v1, json!({"meta-spec": {}}), v2];
+
+        let mut results = validator.validate_iter(metas.iter());
+        assert_eq!(1, results.next().unwrap()?);
+        match results.next().unwrap() {
+            Ok(_) => panic!("invalid metadata unexpectedly succeeded"),
+            Err(e) => assert_eq!("cannot determine meta-spec version", e.to_string()),
+        }
+        assert_eq!(2, results.next().unwrap()?);
+        assert!(results.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_preloaded() -> Result<(), Error> {
+        let mut validator = Validator::new_preloaded()?;
+        let (v1, v2) = load_minimal()?;
+        assert_eq!(1, validator.validate(&v1)?);
+        assert_eq!(2, validator.validate(&v2)?);
+        Ok(())
+    }
+
     fn load_minimal() -> Result<(Value, Value), Error> {
         let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus"].iter().collect();
         let file = dir.join("v1").join("howto.json");
@@ -461,4 +1517,62 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_validate_certs() -> Result<(), Error> {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+        let mut validator = Validator::new();
+        let payload = json!({
+          "user": "theory",
+          "date": "2024-09-13T17:32:55Z",
+          "uri": "dist/pair/0.1.7/pair-0.1.7.zip",
+          "digests": {
+            "sha256": "257b71aa57a28d62ddbb301333b3521ea3dc56f17551fa0e4516b03998abb089"
+          }
+        });
+        let certs = |payload: String| {
+            json!({
+              "pgxn": {
+                "payload": payload,
+                "signature": "DtEhU3ljbEg8L38VWAfUAqOyKAM6-Xx-F4GawxaepmXFCgfTjDxw5djxLa8ISlSApmWQxfKTUJqPP3-Kg6NU1Q",
+              },
+              "x_ab": {"kid": "anna"},
+            })
+        };
+
+        // Validate well-formed certs.
+        validator.validate_certs(&certs(URL_SAFE_NO_PAD.encode(payload.to_string())))?;
+
+        // Fail on a payload that is not Base 64 URL-encoded.
+        let bad = "abcdefghijklm".to_string();
+        match validator.validate_certs(&certs(bad)) {
+            Ok(_) => panic!("bad base64 unexpectedly passed"),
+            Err(e) => assert_eq!(
+                "invalid v2 pgxn payload value: \"abcdefghijklm\"",
+                e.to_string()
+            ),
+        }
+
+        // Fail on a payload that is not JSON.
+        match validator.validate_certs(&certs(URL_SAFE_NO_PAD.encode("not json at all"))) {
+            Ok(_) => panic!("non-JSON payload unexpectedly passed"),
+            Err(e) => assert!(matches!(e, Error::Serde { .. }), "{e}"),
+        }
+
+        // Fail on an invalid payload.
+        let mut pay = payload.clone();
+        json_patch::merge(&mut pay, &json!({"user": null}));
+        match validator.validate_certs(&certs(URL_SAFE_NO_PAD.encode(pay.to_string()))) {
+            Ok(_) => panic!("invalid payload unexpectedly passed"),
+            Err(e) => assert!(e.to_string().contains("missing properties 'user'"), "{e}"),
+        }
+
+        // Fail on invalid certs.
+        match validator.validate_certs(&json!({"x_ab": true})) {
+            Ok(_) => panic!("missing pgxn unexpectedly passed"),
+            Err(e) => assert!(e.to_string().contains("missing properties 'pgxn'"), "{e}"),
+        }
+
+        Ok(())
+    }
 }